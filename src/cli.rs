@@ -3,23 +3,30 @@ use std::path::PathBuf;
 use anyhow::{anyhow, Context, Result};
 use clap::{ArgAction, Parser, ValueEnum};
 use futures::future::try_join_all;
-use http::Uri;
 use log::{debug, Level as LogLevel};
-use url::Url;
 
 use crate::config::{
     load_config_file, ConfigFileNodeOrNodes, ConfigFileProviderOrProviders,
     ConfigFileSortRuleOrSortRules, ConfigFileTemplate, ConfigFileTemplateOrTemplates, MergedConfig,
     SortRules,
 };
-use crate::provider::{Clash, Providers, Ssr};
+use crate::provider::{
+    Clash, Provider, ProviderSource, Providers, SingBox, Sip008, Ssr, Surge, UriList, Wireguard,
+};
 use crate::template::get_built_in_templates;
-use crate::utils::parse_string_to_path;
+use crate::utils::{
+    get_default_cache_directory, parse_proxy, parse_string_to_path, FetchOptions, Path,
+};
 
 #[derive(Debug, ValueEnum, Clone)]
 pub enum CliProviderType {
     Ssr,
     Clash,
+    Wireguard,
+    Sip008,
+    UriList,
+    SingBox,
+    Surge,
 }
 
 /// CLI arguments.
@@ -34,9 +41,9 @@ pub struct Opts {
     #[arg(short = 'p', long)]
     provider_type: Vec<CliProviderType>,
 
-    /// Set the provider URL. Could be used multiple times for setting multiple providers.
+    /// Set the provider URL or local path. Could be used multiple times for setting multiple providers.
     #[arg(short = 'u', long)]
-    provider_url: Vec<Url>,
+    provider_url: Vec<String>,
 
     /// Whether using the built-in templates.
     /// If not set, will automatically set to `true` when there are no user-specified templates,
@@ -49,10 +56,31 @@ pub struct Opts {
     #[arg(short = 't', long)]
     template: Vec<String>,
 
+    /// The HTTP or SOCKS5 proxy used when fetching providers, config files and templates,
+    /// e.g. `http://127.0.0.1:8080` or `socks5://127.0.0.1:1080`.
+    /// Providers with their own `fetch_proxy` option are not affected.
+    /// If not set, the proxy in environment variables (e.g. `HTTPS_PROXY`, `ALL_PROXY`) will be used,
+    /// unless the URL is excluded by `NO_PROXY`.
+    #[arg(long, value_name = "URL")]
+    fetch_proxy: Option<String>,
+
     /// The path of the output directory.
     #[arg(short = 'o', long, value_name = "PATH")]
     output_directory: Option<PathBuf>,
 
+    /// The directory to cache the last successfully fetched content of providers,
+    /// which is used when fetching a provider fails.
+    /// The cache is enabled by default, and the content of providers fetched from URLs
+    /// (including the credentials of nodes) is written to this directory on every run.
+    /// Defaults to `ringer` in the user's cache directory (e.g. `~/.cache/ringer`).
+    #[arg(long, value_name = "PATH")]
+    cache_dir: Option<PathBuf>,
+
+    /// Disable the content cache of providers, so nothing is read from or written to disk.
+    /// Use the `cache` option of a provider to disable it for that provider only.
+    #[arg(long, conflicts_with = "cache_dir")]
+    no_cache: bool,
+
     /// A level of verbosity, and can be used multiple times
     #[arg(short, long, action = ArgAction::Count)]
     verbose: u8,
@@ -69,15 +97,30 @@ pub async fn get_config() -> Result<MergedConfig> {
         ));
     }
 
+    let fetch_options = FetchOptions {
+        proxy: cli_config
+            .fetch_proxy
+            .as_deref()
+            .map(parse_proxy)
+            .transpose()
+            .context("failed to parse `fetch_proxy` in CLI arguments")?,
+        ..Default::default()
+    };
+
     let providers_from_cli: Vec<Providers> = cli_config
         .provider_type
         .iter()
-        .enumerate()
-        .map(|(index, ty)| {
-            let url = cli_config.provider_url[index].as_str().parse().unwrap();
-            get_provider_from_cli_input(ty, url)
+        .zip(cli_config.provider_url)
+        .map(|(ty, provider_url)| {
+            let source = match parse_string_to_path(provider_url)
+                .context("failed to parse the provider URL in CLI arguments")?
+            {
+                Path::Url(url) => ProviderSource::Url(url),
+                Path::PathBuf(path) => ProviderSource::Path(path),
+            };
+            Ok(get_provider_from_cli_input(ty, source))
         })
-        .collect();
+        .collect::<Result<_>>()?;
 
     let mut config_file_templates_from_cli: Vec<_> = cli_config
         .template
@@ -91,12 +134,12 @@ pub async fn get_config() -> Result<MergedConfig> {
         })
         .collect();
 
-    let (providers, standalone_nodes, sort_rules, config_file_templates, output_directory) =
+    let (mut providers, standalone_nodes, sort_rules, config_file_templates, output_directory) =
         if let Some(config_file_path_string) = cli_config.config {
             let config_file_path = parse_string_to_path(config_file_path_string)
                 .context("failed to parse config file path")?;
             let config_file = {
-                let mut config_file = load_config_file(config_file_path.clone())
+                let mut config_file = load_config_file(config_file_path.clone(), &fetch_options)
                     .await
                     .context("failed to load config file")?;
                 config_file
@@ -108,7 +151,7 @@ pub async fn get_config() -> Result<MergedConfig> {
             let providers = if let Some(providers_from_config_file) = config_file.provider {
                 match providers_from_config_file {
                     ConfigFileProviderOrProviders::Provider(p) => {
-                        let providers_from_config_file = [p];
+                        let providers_from_config_file = [*p];
                         providers_from_config_file
                             .into_iter()
                             .chain(providers_from_cli)
@@ -185,6 +228,16 @@ pub async fn get_config() -> Result<MergedConfig> {
             )
         };
 
+    // The proxy of each provider has higher priority than the global one.
+    if let Some(fetch_proxy) = &cli_config.fetch_proxy {
+        for provider in providers.iter_mut() {
+            let options = provider.get_options_mut();
+            if options.fetch_proxy.is_none() {
+                options.fetch_proxy = Some(fetch_proxy.clone());
+            }
+        }
+    }
+
     debug!(
         "templates from CLI arguments and config file:\n{:?}",
         &config_file_templates
@@ -193,7 +246,7 @@ pub async fn get_config() -> Result<MergedConfig> {
     let templates = {
         let template_futures = config_file_templates
             .into_iter()
-            .map(|cft| async { cft.into_tempalte().await });
+            .map(|cft| async { cft.into_tempalte(&fetch_options).await });
 
         let mut templates = try_join_all(template_futures)
             .await
@@ -208,6 +261,12 @@ pub async fn get_config() -> Result<MergedConfig> {
         templates
     };
 
+    let cache_directory = if cli_config.no_cache {
+        None
+    } else {
+        cli_config.cache_dir.or_else(get_default_cache_directory)
+    };
+
     let log_level = match cli_config.verbose {
         0 => LogLevel::Warn,
         1 => LogLevel::Info,
@@ -221,20 +280,49 @@ pub async fn get_config() -> Result<MergedConfig> {
         sort_rules,
         templates,
         output_directory,
+        cache_directory,
         log_level,
     })
 }
 
-fn get_provider_from_cli_input(provider_type: &CliProviderType, url: Uri) -> Providers {
+fn get_provider_from_cli_input(
+    provider_type: &CliProviderType,
+    source: ProviderSource,
+) -> Providers {
     match provider_type {
         CliProviderType::Ssr => Providers::Ssr(Ssr {
             name: None,
-            url,
+            source,
             options: Default::default(),
         }),
         CliProviderType::Clash => Providers::Clash(Clash {
             name: None,
-            url,
+            source,
+            options: Default::default(),
+        }),
+        CliProviderType::Wireguard => Providers::Wireguard(Wireguard {
+            name: None,
+            source,
+            options: Default::default(),
+        }),
+        CliProviderType::Sip008 => Providers::Sip008(Sip008 {
+            name: None,
+            source,
+            options: Default::default(),
+        }),
+        CliProviderType::UriList => Providers::UriList(UriList {
+            name: None,
+            source,
+            options: Default::default(),
+        }),
+        CliProviderType::SingBox => Providers::SingBox(SingBox {
+            name: None,
+            source,
+            options: Default::default(),
+        }),
+        CliProviderType::Surge => Providers::Surge(Surge {
+            name: None,
+            source,
             options: Default::default(),
         }),
     }
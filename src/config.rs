@@ -7,9 +7,9 @@ use serde::Deserialize;
 use url::Url;
 
 use crate::node::Node;
-use crate::provider::{Provider, Providers};
+use crate::provider::{Provider, ProviderSource, Providers};
 use crate::template::Template;
-use crate::utils::{load_content_from_url, parse_string_to_path, Path};
+use crate::utils::{load_content_from_url, parse_string_to_path, FetchOptions, Path};
 
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields, tag = "type", rename_all = "snake_case")]
@@ -180,25 +180,31 @@ impl ConfigFile {
             Path::Url(url) => {
                 let url = Url::parse(url.to_string().as_str()).unwrap();
 
-                if let Some(providers) = &self.provider {
-                    let mut providers = providers.clone();
+                if let Some(providers) = &mut self.provider {
+                    // A remote config file must never be able to run programs on this machine,
+                    // so command providers are only allowed in local config files.
+                    if let Some(command) = providers.iter_mut().find_map(|p| match p {
+                        Providers::Command(command) => Some(command),
+                        _ => None,
+                    }) {
+                        return Err(anyhow!(
+                            "command provider `{}` is not allowed in a remote config file",
+                            command.get_display_name()
+                        ));
+                    }
 
-                    match providers {
-                        ConfigFileProviderOrProviders::Provider(ref mut p) => {
-                            if let Ok(new_url) = url.join(p.get_url().to_string().as_str()) {
-                                p.set_url(new_url.as_str().parse().unwrap());
-                            }
-                        }
-                        ConfigFileProviderOrProviders::Providers(ref mut ps) => {
-                            for ref mut p in ps {
-                                if let Ok(new_url) = url.join(p.get_url().to_string().as_str()) {
-                                    p.set_url(new_url.as_str().parse().unwrap());
-                                };
+                    for source in providers.iter_mut().filter_map(|p| p.get_source_mut()) {
+                        let new_url = match source {
+                            ProviderSource::Url(p_url) => url.join(p_url.to_string().as_str()),
+                            ProviderSource::Path(p_path) => {
+                                url.join(p_path.to_string_lossy().as_ref())
                             }
+                            ProviderSource::Content(_) => continue,
+                        };
+                        if let Ok(new_url) = new_url {
+                            *source = ProviderSource::Url(new_url.as_str().parse().unwrap());
                         }
                     }
-
-                    self.provider = Some(providers);
                 }
 
                 if let Some(templates) = &self.template {
@@ -224,6 +230,28 @@ impl ConfigFile {
             }
 
             Path::PathBuf(path_buf) => {
+                if let Some(providers) = &mut self.provider {
+                    for source in providers.iter_mut().filter_map(|p| p.get_source_mut()) {
+                        if let ProviderSource::Path(p_path) = source {
+                            let mut new_path = path_buf.parent().unwrap().to_path_buf();
+                            new_path.push(&p_path);
+                            *p_path = new_path;
+                        }
+                    }
+
+                    // Programs like `./fetch.sh` are relative to the config file as well,
+                    // while bare program names are still looked up in `PATH`.
+                    for p in providers.iter_mut() {
+                        if let Providers::Command(command) = p {
+                            if command.command.starts_with('.') {
+                                let mut new_path = path_buf.parent().unwrap().to_path_buf();
+                                new_path.push(&command.command);
+                                command.command = new_path.to_string_lossy().to_string();
+                            }
+                        }
+                    }
+                }
+
                 if let Some(templates) = &self.template {
                     let mut templates = templates.clone();
 
@@ -262,9 +290,17 @@ impl ConfigFile {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(untagged, deny_unknown_fields)]
 pub enum ConfigFileProviderOrProviders {
-    Provider(Providers),
+    Provider(Box<Providers>),
     Providers(Vec<Providers>),
 }
+impl ConfigFileProviderOrProviders {
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Providers> {
+        match self {
+            Self::Provider(provider) => std::slice::from_mut(provider.as_mut()).iter_mut(),
+            Self::Providers(providers) => providers.iter_mut(),
+        }
+    }
+}
 
 #[derive(Debug, Deserialize)]
 #[serde(untagged, deny_unknown_fields)]
@@ -308,7 +344,7 @@ pub struct ConfigFileTemplate {
 }
 
 impl ConfigFileTemplate {
-    pub async fn into_tempalte(self) -> Result<Template> {
+    pub async fn into_tempalte(self, fetch_options: &FetchOptions) -> Result<Template> {
         let file_name = self.file_name.map(Ok).unwrap_or_else(|| {
             self.path
                 .split('/')
@@ -322,7 +358,7 @@ impl ConfigFileTemplate {
         let path =
             parse_string_to_path(self.path).context("failed to parse path in the template")?;
 
-        let content = load_content_from_url(path)
+        let content = load_content_from_url(path, fetch_options)
             .await
             .context("failed to load template")?;
 
@@ -344,8 +380,8 @@ pub enum ConfigFileTemplateOrTemplates {
 }
 
 /// Load a config file from an URL.
-pub async fn load_config_file(path: Path) -> Result<ConfigFile> {
-    let contents_bytes = load_content_from_url(path).await?;
+pub async fn load_config_file(path: Path, fetch_options: &FetchOptions) -> Result<ConfigFile> {
+    let contents_bytes = load_content_from_url(path, fetch_options).await?;
 
     let contents = std::str::from_utf8(&contents_bytes)
         .context("failed to convert config file contents to string.")?;
@@ -366,5 +402,157 @@ pub struct MergedConfig {
 
     pub output_directory: PathBuf,
 
+    /// The directory of the content cache of providers.
+    /// `None` means the cache is disabled.
+    pub cache_directory: Option<PathBuf>,
+
     pub log_level: LogLevel,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_provider_sources(config: &mut ConfigFile) -> Vec<String> {
+        config
+            .provider
+            .as_mut()
+            .unwrap()
+            .iter_mut()
+            .filter_map(|provider| provider.get_source())
+            .map(|source| match source {
+                ProviderSource::Url(url) => url.to_string(),
+                ProviderSource::Path(path) => path.to_string_lossy().to_string(),
+                ProviderSource::Content(content) => content.clone(),
+            })
+            .collect()
+    }
+
+    fn get_template_paths(config: &ConfigFile) -> Vec<&str> {
+        match config.template.as_ref().unwrap() {
+            ConfigFileTemplateOrTemplates::Template(template) => vec![template.path.as_str()],
+            ConfigFileTemplateOrTemplates::Templates(templates) => templates
+                .iter()
+                .map(|template| template.path.as_str())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn rewrite_paths_in_local_config() {
+        let mut config: ConfigFile = toml::from_str(
+            r#"
+[[provider]]
+type = "clash"
+path = "subscriptions/clash.yaml"
+
+[[provider]]
+type = "clash"
+url = "https://example.com/clash.yaml"
+
+[[provider]]
+type = "command"
+command = "./fetch.sh"
+format = "uri-list"
+
+[[provider]]
+type = "command"
+command = "curl"
+format = "uri-list"
+
+[[template]]
+path = "./templates/clash.yaml"
+"#,
+        )
+        .unwrap();
+
+        config
+            .rewrite_relative_path(Path::PathBuf(PathBuf::from("/etc/ringer/config.toml")))
+            .unwrap();
+
+        assert_eq!(
+            get_provider_sources(&mut config),
+            [
+                "/etc/ringer/subscriptions/clash.yaml",
+                "https://example.com/clash.yaml"
+            ]
+        );
+
+        let commands: Vec<_> = config
+            .provider
+            .as_mut()
+            .unwrap()
+            .iter_mut()
+            .filter_map(|provider| match provider {
+                Providers::Command(command) => Some(command.command.as_str()),
+                _ => None,
+            })
+            .collect();
+        // Bare program names are still looked up in `PATH`.
+        assert_eq!(commands, ["/etc/ringer/./fetch.sh", "curl"]);
+
+        assert_eq!(
+            get_template_paths(&config),
+            ["/etc/ringer/./templates/clash.yaml"]
+        );
+    }
+
+    #[test]
+    fn rewrite_paths_in_remote_config() {
+        let mut config: ConfigFile = toml::from_str(
+            r#"
+[[provider]]
+type = "clash"
+path = "subscriptions/clash.yaml"
+
+[[provider]]
+type = "clash"
+url = "https://other.example.com/clash.yaml"
+
+[[template]]
+path = "./templates/clash.yaml"
+"#,
+        )
+        .unwrap();
+
+        config
+            .rewrite_relative_path(Path::Url(
+                "https://example.com/ringer/config.toml".parse().unwrap(),
+            ))
+            .unwrap();
+
+        assert_eq!(
+            get_provider_sources(&mut config),
+            [
+                "https://example.com/ringer/subscriptions/clash.yaml",
+                "https://other.example.com/clash.yaml"
+            ]
+        );
+        assert_eq!(
+            get_template_paths(&config),
+            ["https://example.com/ringer/templates/clash.yaml"]
+        );
+    }
+
+    #[test]
+    fn reject_command_provider_in_remote_config() {
+        let mut config: ConfigFile = toml::from_str(
+            r#"
+[[provider]]
+type = "command"
+command = "./fetch.sh"
+format = "uri-list"
+"#,
+        )
+        .unwrap();
+
+        let err = config
+            .rewrite_relative_path(Path::Url(
+                "https://example.com/ringer/config.toml".parse().unwrap(),
+            ))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("is not allowed in a remote config file"));
+    }
+}
@@ -11,6 +11,7 @@ use config::MergedConfig;
 use node::Node;
 use provider::Provider;
 use template::{RenderEngine, TemplateArgs};
+use utils::ContentCache;
 
 mod cli;
 mod config;
@@ -38,17 +39,23 @@ async fn main() -> Result<()> {
         warn!("no providers");
     }
 
+    let cache = config.cache_directory.clone().map(ContentCache::new);
+
     let nodes_futures = config.providers.iter().map(|provider| async {
         debug!(
             "start fetching content of provider `{}`...",
             provider.get_display_name(),
         );
-        let content = provider.fetch_content().await.with_context(|| {
-            format!(
-                "failed to fetch content of provider:\n{}",
-                provider.get_display_name()
-            )
-        })?;
+        let cache_key = provider.get_cache_key();
+        let (content, is_from_cache) = provider
+            .fetch_content_or_cached(cache.as_ref())
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to fetch content of provider:\n{}",
+                    provider.get_display_name()
+                )
+            })?;
         trace!(
             "content of provider `{}`:\n{:?}",
             provider.get_display_name(),
@@ -56,7 +63,7 @@ async fn main() -> Result<()> {
         );
 
         let nodes = provider
-            .parse_nodes_from_content(content)
+            .parse_nodes_from_content(content.clone())
             .with_context(|| {
                 format!(
                     "failed to parse nodes of provider:\n{}",
@@ -70,6 +77,17 @@ async fn main() -> Result<()> {
             &nodes
         );
 
+        // Only cache the content which can be parsed successfully.
+        if let (Some(cache), Some(cache_key), false) = (&cache, &cache_key, is_from_cache) {
+            if let Err(e) = cache.store(cache_key, &content).await {
+                warn!(
+                    "failed to cache content of provider `{}`: {:#}",
+                    provider.get_display_name(),
+                    e
+                );
+            }
+        }
+
         std::result::Result::<Vec<Node>, Error>::Ok(nodes)
     });
 
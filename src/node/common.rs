@@ -1,8 +1,239 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TlsOptions {
     pub sni: Option<String>,
     pub insecure: Option<bool>,
     pub alpn: Option<Vec<String>>,
 }
+impl TlsOptions {
+    /// Build the TLS options from the query of V2Ray-style share links
+    /// (e.g. `vless://`), which uses `sni`, `allowInsecure` and `alpn`.
+    pub fn from_share_link_query(query: &HashMap<String, String>) -> Self {
+        Self {
+            sni: get_non_empty_query_value(query, "sni"),
+            insecure: get_non_empty_query_value(query, "allowInsecure")
+                .map(|insecure| insecure == "1" || insecure == "true"),
+            alpn: get_non_empty_query_value(query, "alpn")
+                .map(|alpn| alpn.split(',').map(String::from).collect()),
+        }
+    }
+}
+
+/// The transport layer used by V2Ray-like protocols (e.g. VMess).
+/// Raw TCP is the default transport, which is represented by `None`.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum Transport {
+    /// WebSocket
+    Ws {
+        path: Option<String>,
+        host: Option<String>,
+    },
+
+    /// HTTP/2
+    H2 {
+        path: Option<String>,
+        host: Option<Vec<String>>,
+    },
+
+    /// gRPC
+    Grpc { service_name: Option<String> },
+}
+impl Transport {
+    /// Build the transport from the parameters used in share links,
+    /// where `network` is something like `tcp`, `ws`, `h2` or `grpc`.
+    pub fn from_share_link_params(
+        network: &str,
+        host: Option<String>,
+        path: Option<String>,
+        service_name: Option<String>,
+    ) -> Result<Option<Self>> {
+        match network {
+            "" | "tcp" => Ok(None),
+            "ws" => Ok(Some(Self::Ws { path, host })),
+            "h2" | "http" => Ok(Some(Self::H2 {
+                path,
+                host: host.map(|host| host.split(',').map(String::from).collect()),
+            })),
+            "grpc" => Ok(Some(Self::Grpc { service_name })),
+            _ => Err(anyhow!("Unsupported transport `{}`", network)),
+        }
+    }
+
+    /// Build the transport from the query of V2Ray-style share links
+    /// (e.g. `vless://`), which uses `type`, `host`, `path` and `serviceName`.
+    pub fn from_share_link_query(query: &HashMap<String, String>) -> Result<Option<Self>> {
+        Self::from_share_link_params(
+            query.get("type").map_or("", String::as_str),
+            get_non_empty_query_value(query, "host"),
+            get_non_empty_query_value(query, "path"),
+            get_non_empty_query_value(query, "serviceName"),
+        )
+    }
+}
+
+pub fn get_non_empty_query_value(query: &HashMap<String, String>, key: &str) -> Option<String> {
+    query.get(key).filter(|value| !value.is_empty()).cloned()
+}
+
+/// The server port of QUIC-based protocols (e.g. Hysteria),
+/// which could be a single port or a port hopping range.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ServerPort {
+    Single(u16),
+    Range(u16, u16),
+}
+impl ServerPort {
+    #[allow(dead_code)]
+    pub fn is_single(&self) -> bool {
+        match self {
+            Self::Single(_) => true,
+            Self::Range(_, _) => false,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_range(&self) -> bool {
+        match self {
+            Self::Single(_) => false,
+            Self::Range(_, _) => true,
+        }
+    }
+
+    pub fn get_start_port(&self) -> u16 {
+        match self {
+            Self::Single(port) => *port,
+            Self::Range(start, _) => *start,
+        }
+    }
+
+    /// Parse the port from strings like `443` or `20000-50000`.
+    pub fn from_port_str(s: &str) -> Result<Self> {
+        let parse_port = |port: &str| {
+            port.trim()
+                .parse()
+                .with_context(|| format!("invalid port `{}`", port.trim()))
+        };
+
+        match s.split_once('-') {
+            Some((start, end)) => Ok(Self::Range(parse_port(start)?, parse_port(end)?)),
+            None => Ok(Self::Single(parse_port(s)?)),
+        }
+    }
+}
+
+/// The bandwidth of QUIC-based protocols (e.g. Hysteria).
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Speed {
+    Text(String),
+    Mbps(u32),
+}
+impl Speed {
+    /// Parse the speed from the value of share links,
+    /// which is in Mbps if it's a plain number, otherwise it might contain a unit.
+    pub fn from_mbps_str(s: &str) -> Self {
+        match s.parse() {
+            Ok(mbps) => Self::Mbps(mbps),
+            Err(_) => Self::Text(s.to_string()),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn to_mbps(&self) -> Option<u32> {
+        match self {
+            Self::Text(text) => {
+                for b in [" bps", "bps", " b", "b"] {
+                    if text.ends_with(b) {
+                        return text
+                            .trim_end_matches(b)
+                            .parse::<u64>()
+                            .ok()
+                            .map(|n| (n / 1024 / 1024) as u32);
+                    }
+                }
+
+                for k in [" kbps", "kbps", " kb", "kb", " k", "k"] {
+                    if text.ends_with(k) {
+                        return text
+                            .trim_end_matches(k)
+                            .parse::<u64>()
+                            .ok()
+                            .map(|n| (n / 1024) as u32);
+                    }
+                }
+
+                for m in [" mbps", "mbps", " mb", "mb", " m", "m"] {
+                    if text.ends_with(m) {
+                        return text.trim_end_matches(m).parse::<u32>().ok();
+                    }
+                }
+
+                for g in [" gbps", "gbps", " gb", "gb", " g", "g"] {
+                    if text.ends_with(g) {
+                        return text
+                            .trim_end_matches(g)
+                            .parse::<u32>()
+                            .ok()
+                            .map(|n| n * 1024);
+                    }
+                }
+
+                for t in [" tbps", "tbps", " tb", "tb", " t", "t"] {
+                    if text.ends_with(t) {
+                        return text
+                            .trim_end_matches(t)
+                            .parse::<u32>()
+                            .ok()
+                            .map(|n| n * 1024 * 1024);
+                    }
+                }
+
+                None
+            }
+
+            Self::Mbps(mbps) => Some(*mbps),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Mbps(mbps) => format!("{mbps} mbps"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_server_port() {
+        assert_eq!(
+            ServerPort::from_port_str("443").unwrap(),
+            ServerPort::Single(443)
+        );
+        assert_eq!(
+            ServerPort::from_port_str("20000 - 50000").unwrap(),
+            ServerPort::Range(20000, 50000)
+        );
+        assert!(ServerPort::from_port_str("20000,30000").is_err());
+        assert!(ServerPort::from_port_str("65536").is_err());
+    }
+
+    #[test]
+    fn parse_speed() {
+        assert_eq!(Speed::from_mbps_str("100"), Speed::Mbps(100));
+        assert_eq!(
+            Speed::from_mbps_str("100 mbps"),
+            Speed::Text(String::from("100 mbps"))
+        );
+    }
+}
@@ -1,8 +1,14 @@
+use std::collections::HashMap;
 use std::fmt::Display;
 
+use anyhow::{anyhow, Context, Result};
+use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Serialize};
+use url::Url;
 
-use crate::node::common::TlsOptions;
+pub use crate::node::common::{ServerPort, Speed};
+
+use crate::node::common::{get_non_empty_query_value, TlsOptions};
 
 /// The configuration of a Hysteria node.
 /// Reference: https://v1.hysteria.network/docs/advanced-usage/#client
@@ -19,6 +25,72 @@ pub struct HysteriaNode {
     pub auth: Option<String>,
     pub tls: TlsOptions,
 }
+impl HysteriaNode {
+    /// Convert a Hysteria link to a Hysteria node.
+    /// Reference: [Hysteria URI scheme](https://v1.hysteria.network/docs/uri-scheme/)
+    /// ```
+    /// hysteria://host:port?protocol=udp&auth=...&peer=...&insecure=1&upmbps=100&downmbps=100&alpn=hysteria&obfs=xplus&obfsParam=...#remarks
+    /// ```
+    pub fn from_url(url: &Url) -> Result<Self> {
+        let server = url
+            .host_str()
+            .ok_or_else(|| anyhow!("Hysteria link `{}` does not contain server.", url))?
+            .to_string();
+
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        let port = if let Some(mport) = get_non_empty_query_value(&query, "mport") {
+            ServerPort::from_port_str(&mport)
+                .with_context(|| format!("Invalid `mport` `{mport}` in Hysteria link `{url}`"))?
+        } else {
+            ServerPort::Single(
+                url.port()
+                    .ok_or_else(|| anyhow!("Hysteria link `{}` does not contain port.", url))?,
+            )
+        };
+
+        let protocol = get_non_empty_query_value(&query, "protocol")
+            .map(|protocol| {
+                Protocol::from_alias(&protocol).ok_or_else(|| {
+                    anyhow!("Unknown protocol `{}` in Hysteria link `{}`", protocol, url)
+                })
+            })
+            .transpose()?;
+
+        let up = get_non_empty_query_value(&query, "upmbps")
+            .as_deref()
+            .map(Speed::from_mbps_str)
+            .ok_or_else(|| anyhow!("Hysteria link `{}` does not contain `upmbps`.", url))?;
+        let down = get_non_empty_query_value(&query, "downmbps")
+            .as_deref()
+            .map(Speed::from_mbps_str)
+            .ok_or_else(|| anyhow!("Hysteria link `{}` does not contain `downmbps`.", url))?;
+
+        let tls = TlsOptions {
+            sni: get_non_empty_query_value(&query, "peer"),
+            insecure: get_non_empty_query_value(&query, "insecure")
+                .map(|insecure| insecure == "1" || insecure == "true"),
+            alpn: get_non_empty_query_value(&query, "alpn")
+                .map(|alpn| alpn.split(',').map(String::from).collect()),
+        };
+
+        let remarks = url
+            .fragment()
+            .map(|remarks| percent_decode_str(remarks).decode_utf8_lossy().to_string());
+
+        Ok(Self {
+            remarks,
+            server,
+            port,
+            protocol,
+            up,
+            down,
+            obfs: get_non_empty_query_value(&query, "obfsParam"),
+            auth: get_non_empty_query_value(&query, "auth"),
+            tls,
+        })
+    }
+}
 impl super::GetNodeName for HysteriaNode {
     fn get_name(&self) -> Option<&String> {
         self.remarks.as_ref()
@@ -33,37 +105,6 @@ impl super::GetNodeName for HysteriaNode {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum ServerPort {
-    Single(u16),
-    Range(u16, u16),
-}
-impl ServerPort {
-    #[allow(dead_code)]
-    pub fn is_single(&self) -> bool {
-        match self {
-            Self::Single(_) => true,
-            Self::Range(_, _) => false,
-        }
-    }
-
-    #[allow(dead_code)]
-    pub fn is_range(&self) -> bool {
-        match self {
-            Self::Single(_) => false,
-            Self::Range(_, _) => true,
-        }
-    }
-
-    pub fn get_start_port(&self) -> u16 {
-        match self {
-            Self::Single(port) => *port,
-            Self::Range(start, _) => *start,
-        }
-    }
-}
-
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Protocol {
@@ -74,6 +115,17 @@ pub enum Protocol {
     #[serde(rename = "faketcp")]
     FakeTcp,
 }
+impl Protocol {
+    pub fn from_alias(alias: &str) -> Option<Self> {
+        match alias {
+            "udp" => Some(Self::Udp),
+            "wechat-video" => Some(Self::WechatVideo),
+            "faketcp" => Some(Self::FakeTcp),
+
+            _ => None,
+        }
+    }
+}
 impl Display for Protocol {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -84,75 +136,50 @@ impl Display for Protocol {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum Speed {
-    Text(String),
-    Mbps(u32),
-}
-impl Speed {
-    #[allow(dead_code)]
-    pub fn to_mbps(&self) -> Option<u32> {
-        match self {
-            Self::Text(text) => {
-                for b in [" bps", "bps", " b", "b"] {
-                    if text.ends_with(b) {
-                        return text
-                            .trim_end_matches(b)
-                            .parse::<u64>()
-                            .ok()
-                            .map(|n| (n / 1024 / 1024) as u32);
-                    }
-                }
-
-                for k in [" kbps", "kbps", " kb", "kb", " k", "k"] {
-                    if text.ends_with(k) {
-                        return text
-                            .trim_end_matches(k)
-                            .parse::<u64>()
-                            .ok()
-                            .map(|n| (n / 1024) as u32);
-                    }
-                }
-
-                for m in [" mbps", "mbps", " mb", "mb", " m", "m"] {
-                    if text.ends_with(m) {
-                        return text.trim_end_matches(m).parse::<u32>().ok();
-                    }
-                }
-
-                for g in [" gbps", "gbps", " gb", "gb", " g", "g"] {
-                    if text.ends_with(g) {
-                        return text
-                            .trim_end_matches(g)
-                            .parse::<u32>()
-                            .ok()
-                            .map(|n| n * 1024);
-                    }
-                }
-
-                for t in [" tbps", "tbps", " tb", "tb", " t", "t"] {
-                    if text.ends_with(t) {
-                        return text
-                            .trim_end_matches(t)
-                            .parse::<u32>()
-                            .ok()
-                            .map(|n| n * 1024 * 1024);
-                    }
-                }
-
-                None
-            }
-
-            Self::Mbps(mbps) => Some(*mbps),
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hysteria_link() {
+        let link = Url::parse("hysteria://192.168.100.1:443?protocol=udp&auth=123456&peer=example.com&insecure=1&upmbps=100&downmbps=500&alpn=hysteria&obfs=xplus&obfsParam=abcdef#Example%20Hysteria").unwrap();
+        let node = HysteriaNode {
+            remarks: Some(String::from("Example Hysteria")),
+            server: String::from("192.168.100.1"),
+            port: ServerPort::Single(443),
+            protocol: Some(Protocol::Udp),
+            up: Speed::Mbps(100),
+            down: Speed::Mbps(500),
+            obfs: Some(String::from("abcdef")),
+            auth: Some(String::from("123456")),
+            tls: TlsOptions {
+                sni: Some(String::from("example.com")),
+                insecure: Some(true),
+                alpn: Some(vec![String::from("hysteria")]),
+            },
+        };
+        assert_eq!(HysteriaNode::from_url(&link).unwrap(), node);
     }
 
-    #[allow(dead_code)]
-    pub fn to_text(&self) -> String {
-        match self {
-            Self::Text(text) => text.clone(),
-            Self::Mbps(mbps) => format!("{mbps} mbps"),
-        }
+    #[test]
+    fn parse_hysteria_link_with_port_range() {
+        let link = Url::parse("hysteria://example.com:443?mport=20000-50000&upmbps=10&downmbps=50")
+            .unwrap();
+        let node = HysteriaNode {
+            remarks: None,
+            server: String::from("example.com"),
+            port: ServerPort::Range(20000, 50000),
+            protocol: None,
+            up: Speed::Mbps(10),
+            down: Speed::Mbps(50),
+            obfs: None,
+            auth: None,
+            tls: TlsOptions {
+                sni: None,
+                insecure: None,
+                alpn: None,
+            },
+        };
+        assert_eq!(HysteriaNode::from_url(&link).unwrap(), node);
     }
 }
@@ -2,16 +2,30 @@ use enum_dispatch::enum_dispatch;
 use serde::{Deserialize, Serialize};
 
 pub mod common;
+pub mod http;
 pub mod hysteria;
 pub mod hysteria2;
+pub mod snell;
+pub mod socks5;
 pub mod ss;
 pub mod ssr;
+pub mod trojan;
+pub mod tuic;
+pub mod vless;
+pub mod vmess;
 pub mod wireguard;
 
+pub use http::HttpNode;
 pub use hysteria::HysteriaNode;
 pub use hysteria2::Hysteria2Node;
+pub use snell::SnellNode;
+pub use socks5::Socks5Node;
 pub use ss::SsNode;
 pub use ssr::SsrNode;
+pub use trojan::TrojanNode;
+pub use tuic::TuicNode;
+pub use vless::VlessNode;
+pub use vmess::VmessNode;
 pub use wireguard::WireguardNode;
 
 #[enum_dispatch]
@@ -59,4 +73,18 @@ pub enum Node {
     Hysteria2(Box<Hysteria2Node>),
     #[serde(rename = "wireguard")]
     Wireguard(WireguardNode),
+    #[serde(rename = "vmess")]
+    Vmess(Box<VmessNode>),
+    #[serde(rename = "vless")]
+    Vless(Box<VlessNode>),
+    #[serde(rename = "trojan")]
+    Trojan(Box<TrojanNode>),
+    #[serde(rename = "tuic")]
+    Tuic(Box<TuicNode>),
+    #[serde(rename = "socks5")]
+    Socks5(Box<Socks5Node>),
+    #[serde(rename = "http")]
+    Http(Box<HttpNode>),
+    #[serde(rename = "snell")]
+    Snell(Box<SnellNode>),
 }
@@ -0,0 +1,107 @@
+use anyhow::{anyhow, Result};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::node::ss::ObfsType;
+
+/// The configuration of a Snell node.
+/// Reference: https://manual.nssurge.com/policy/proxy.html
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnellNode {
+    pub remarks: Option<String>,
+    pub server: String,
+    pub port: u16,
+    pub psk: String,
+
+    /// The version of the Snell protocol, e.g. `3` or `4`.
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: Option<u8>,
+
+    pub obfs: Option<SnellObfsOpts>,
+}
+impl SnellNode {
+    /// Check the version of the Snell protocol, which should be within `1..=4`.
+    pub fn check_version(version: u8) -> Result<u8> {
+        if (1..=4).contains(&version) {
+            Ok(version)
+        } else {
+            Err(anyhow!(
+                "Unsupported Snell version `{}`, which should be within 1 to 4",
+                version
+            ))
+        }
+    }
+}
+impl super::GetNodeName for SnellNode {
+    fn get_name(&self) -> Option<&String> {
+        self.remarks.as_ref()
+    }
+
+    fn get_server(&'_ self) -> &'_ String {
+        &self.server
+    }
+
+    fn get_port(&self) -> u16 {
+        self.port
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnellObfsOpts {
+    pub mode: ObfsType,
+    pub host: Option<String>,
+}
+
+fn deserialize_version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u8>, D::Error> {
+    Option::<u8>::deserialize(deserializer)?
+        .map(SnellNode::check_version)
+        .transpose()
+        .map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_snell_node() {
+        let node: SnellNode = toml::from_str(
+            r#"
+remarks = "Snell"
+server = "example.com"
+port = 8000
+psk = "psk"
+version = 4
+obfs = { mode = "Http", host = "bing.com" }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            node,
+            SnellNode {
+                remarks: Some(String::from("Snell")),
+                server: String::from("example.com"),
+                port: 8000,
+                psk: String::from("psk"),
+                version: Some(4),
+                obfs: Some(SnellObfsOpts {
+                    mode: ObfsType::Http,
+                    host: Some(String::from("bing.com")),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn reject_unsupported_version() {
+        for version in [0, 5] {
+            assert!(toml::from_str::<SnellNode>(&format!(
+                "server = \"example.com\"\nport = 8000\npsk = \"psk\"\nversion = {version}"
+            ))
+            .is_err());
+        }
+    }
+}
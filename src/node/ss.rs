@@ -78,21 +78,10 @@ impl SsNode {
         }
         let mut query = url.query_pairs();
         let plugin = if let Some((_, plugin_arg)) = query.find(|(key, _)| key == "plugin") {
-            let mut plugin_arg_parts = plugin_arg.split(';');
-            let plugin_str = plugin_arg_parts
-                .next()
-                .ok_or_else(|| anyhow!("SS link `{}` does not contain plugin.", url))?;
-
-            let plugin_opts = plugin_arg_parts
-                .filter_map(|plugin_arg_str| {
-                    let mut plugin_arg_str_parts = plugin_arg_str.split('=');
-                    let key = plugin_arg_str_parts.next()?;
-                    let value = plugin_arg_str_parts.next().unwrap_or("");
-                    Some((key.to_string(), value.to_string()))
-                })
-                .collect::<BTreeMap<String, String>>();
-
-            let plugin = Plugin::from_name_and_opts(plugin_str.to_string(), plugin_opts)
+            let (plugin_str, plugin_opts_str) =
+                plugin_arg.split_once(';').unwrap_or((&plugin_arg, ""));
+
+            let plugin = Plugin::from_name_and_opts_str(plugin_str.to_string(), plugin_opts_str)
                 .context("failed to parse SS plugin")?;
 
             Some(plugin)
@@ -249,7 +238,7 @@ impl Method {
             "2022-blake3-chacha20-poly1305" => Some(Self::Ss2022Blake3Chacha20Poly1305),
             "2022-blake3-chacha8-poly1305" => Some(Self::Ss2022Blake3Chacha8Poly1305),
 
-            "chacha20-poly1305" => Some(Self::AeadChacha20Poly1305),
+            "chacha20-poly1305" | "chacha20-ietf-poly1305" => Some(Self::AeadChacha20Poly1305),
             "aes-256-gcm" => Some(Self::AeadAes256Gcm),
             "aes-128-gcm" => Some(Self::AeadAes128Gcm),
 
@@ -350,6 +339,21 @@ impl Plugin {
         }
     }
 
+    /// Parse the plugin from its name and the options in SIP003 format,
+    /// e.g. `obfs=http;obfs-host=example.com`.
+    pub fn from_name_and_opts_str(name: String, opts: &str) -> Result<Self> {
+        let opts = opts
+            .split(';')
+            .filter(|opt| !opt.is_empty())
+            .map(|opt| {
+                let (key, value) = opt.split_once('=').unwrap_or((opt, ""));
+                (key.to_string(), value.to_string())
+            })
+            .collect();
+
+        Self::from_name_and_opts(name, opts)
+    }
+
     pub fn plugin_name(&'_ self) -> &'_ str {
         match self {
             Self::SimpleObfs(_) => "simple-obfs",
@@ -567,4 +571,52 @@ mod tests {
             node_with_plugin
         );
     }
+
+    #[test]
+    fn parse_ss_link_plugin_forms() {
+        let parse_plugin = |plugin: &str| {
+            let link = Url::parse(&format!(
+                "ss://YWVzLTEyOC1nY206dGVzdA@192.168.100.1:8888/?plugin={plugin}#Example"
+            ))
+            .unwrap();
+            SsNode::from_url(&link).unwrap().plugin
+        };
+
+        // A plugin without options.
+        assert_eq!(
+            parse_plugin("obfs-local"),
+            Some(Plugin::SimpleObfs(ObfsOpts {
+                obfs: None,
+                host: None,
+                uri: None,
+            }))
+        );
+
+        // Options encoded or not, with the `simple-obfs` alias.
+        let obfs_plugin = Some(Plugin::SimpleObfs(ObfsOpts {
+            obfs: Some(ObfsType::Tls),
+            host: Some(String::from("www.bing.com")),
+            uri: None,
+        }));
+        assert_eq!(
+            parse_plugin("obfs-local%3Bobfs%3Dtls%3Bobfs-host%3Dwww.bing.com"),
+            obfs_plugin
+        );
+        assert_eq!(
+            parse_plugin("simple-obfs;obfs=tls;obfs-host=www.bing.com"),
+            obfs_plugin
+        );
+
+        // Options without values and empty options are kept and skipped respectively.
+        assert_eq!(
+            parse_plugin("custom-plugin%3Bmode%3Dfast%3Bverbose%3B"),
+            Some(Plugin::Unknown {
+                plugin_name: String::from("custom-plugin"),
+                plugin_opts: Some(BTreeMap::from([
+                    (String::from("mode"), String::from("fast")),
+                    (String::from("verbose"), String::new()),
+                ])),
+            })
+        );
+    }
 }
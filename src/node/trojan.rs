@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context, Result};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::node::common::{get_non_empty_query_value, TlsOptions, Transport};
+
+/// The configuration of a Trojan node.
+/// Reference: https://trojan-gfw.github.io/trojan/config
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrojanNode {
+    pub remarks: Option<String>,
+    pub server: String,
+    pub port: u16,
+    pub password: String,
+    pub transport: Option<Transport>,
+    pub tls: TlsOptions,
+}
+impl TrojanNode {
+    /// Convert a Trojan link to a Trojan node.
+    /// Reference: [Trojan URL scheme](https://trojan-gfw.github.io/trojan/url)
+    /// ```
+    /// trojan://password@host:port?sni=...&type=ws&host=...&path=...#remarks
+    /// ```
+    pub fn from_url(url: &Url) -> Result<Self> {
+        let server = url
+            .host_str()
+            .ok_or_else(|| anyhow!("Trojan link `{}` does not contain server.", url))?
+            .to_string();
+
+        let port = url
+            .port()
+            .ok_or_else(|| anyhow!("Trojan link `{}` does not contain port.", url))?;
+
+        let password = percent_decode_str(url.username())
+            .decode_utf8_lossy()
+            .to_string();
+        if password.is_empty() {
+            return Err(anyhow!("Trojan link `{}` does not contain password.", url));
+        }
+
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        let mut tls = TlsOptions::from_share_link_query(&query);
+        // Older clients use `peer` instead of `sni`.
+        if tls.sni.is_none() {
+            tls.sni = get_non_empty_query_value(&query, "peer");
+        }
+
+        let transport = Transport::from_share_link_query(&query)
+            .context("failed to parse the transport of the Trojan link")?;
+
+        let remarks = url
+            .fragment()
+            .map(|remarks| percent_decode_str(remarks).decode_utf8_lossy().to_string());
+
+        Ok(Self {
+            remarks,
+            server,
+            port,
+            password,
+            transport,
+            tls,
+        })
+    }
+}
+impl super::GetNodeName for TrojanNode {
+    fn get_name(&self) -> Option<&String> {
+        self.remarks.as_ref()
+    }
+
+    fn get_server(&'_ self) -> &'_ String {
+        &self.server
+    }
+
+    fn get_port(&self) -> u16 {
+        self.port
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_trojan_link() {
+        let link =
+            Url::parse("trojan://password0@192.168.100.1:443?peer=example.com#Example%20Trojan")
+                .unwrap();
+        let node = TrojanNode {
+            remarks: Some(String::from("Example Trojan")),
+            server: String::from("192.168.100.1"),
+            port: 443,
+            password: String::from("password0"),
+            transport: None,
+            tls: TlsOptions {
+                sni: Some(String::from("example.com")),
+                insecure: None,
+                alpn: None,
+            },
+        };
+        assert_eq!(TrojanNode::from_url(&link).unwrap(), node);
+    }
+
+    #[test]
+    fn parse_trojan_link_with_grpc_transport() {
+        let link = Url::parse(
+            "trojan://password0@example.com:443?security=tls&sni=example.com&allowInsecure=1&type=grpc&serviceName=trojan-grpc",
+        )
+        .unwrap();
+        let node = TrojanNode {
+            remarks: None,
+            server: String::from("example.com"),
+            port: 443,
+            password: String::from("password0"),
+            transport: Some(Transport::Grpc {
+                service_name: Some(String::from("trojan-grpc")),
+            }),
+            tls: TlsOptions {
+                sni: Some(String::from("example.com")),
+                insecure: Some(true),
+                alpn: None,
+            },
+        };
+        assert_eq!(TrojanNode::from_url(&link).unwrap(), node);
+    }
+}
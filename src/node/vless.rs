@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context, Result};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use url::Url;
+use uuid::Uuid;
+
+use crate::node::common::{get_non_empty_query_value, TlsOptions, Transport};
+
+/// The configuration of a VLESS node.
+/// Reference: https://xtls.github.io/en/config/outbounds/vless.html
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VlessNode {
+    pub remarks: Option<String>,
+    pub server: String,
+    pub port: u16,
+    pub uuid: Uuid,
+
+    /// The flow control, e.g. `xtls-rprx-vision`.
+    pub flow: Option<String>,
+
+    pub transport: Option<Transport>,
+    pub tls: Option<TlsOptions>,
+
+    /// The uTLS fingerprint of the client, e.g. `chrome`.
+    pub fingerprint: Option<String>,
+
+    /// REALITY options, which imply TLS even if `tls` is not set.
+    pub reality: Option<RealityOptions>,
+}
+impl VlessNode {
+    /// Whether TLS is enabled, either by `tls` or by REALITY.
+    pub fn is_tls_enabled(&self) -> bool {
+        self.tls.is_some() || self.reality.is_some()
+    }
+
+    /// Convert a VLESS link to a VLESS node.
+    /// Reference: [VMessAEAD / VLESS share link standard](https://github.com/XTLS/Xray-core/discussions/716)
+    /// ```
+    /// vless://uuid@host:port?type=tcp&security=reality&flow=xtls-rprx-vision&sni=...&pbk=...&sid=...#remarks
+    /// ```
+    pub fn from_url(url: &Url) -> Result<Self> {
+        let server = url
+            .host_str()
+            .ok_or_else(|| anyhow!("VLESS link `{}` does not contain server.", url))?
+            .to_string();
+
+        let port = url
+            .port()
+            .ok_or_else(|| anyhow!("VLESS link `{}` does not contain port.", url))?;
+
+        let uuid_str = percent_decode_str(url.username()).decode_utf8_lossy();
+        let uuid = Uuid::parse_str(&uuid_str)
+            .with_context(|| format!("invalid UUID `{uuid_str}` in VLESS link `{url}`"))?;
+
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        let (tls, reality) = match query.get("security").map_or("", String::as_str) {
+            "" | "none" => (None, None),
+            "tls" | "xtls" => (Some(TlsOptions::from_share_link_query(&query)), None),
+            "reality" => {
+                let public_key = get_non_empty_query_value(&query, "pbk").ok_or_else(|| {
+                    anyhow!("VLESS link `{}` does not contain REALITY public key.", url)
+                })?;
+
+                (
+                    Some(TlsOptions::from_share_link_query(&query)),
+                    Some(RealityOptions {
+                        public_key,
+                        short_id: get_non_empty_query_value(&query, "sid"),
+                    }),
+                )
+            }
+            security => {
+                return Err(anyhow!(
+                    "Unknown security `{}` in VLESS link `{}`",
+                    security,
+                    url
+                ))
+            }
+        };
+
+        let transport = Transport::from_share_link_query(&query)
+            .context("failed to parse the transport of the VLESS link")?;
+
+        let remarks = url
+            .fragment()
+            .map(|remarks| percent_decode_str(remarks).decode_utf8_lossy().to_string());
+
+        Ok(Self {
+            remarks,
+            server,
+            port,
+            uuid,
+            flow: get_non_empty_query_value(&query, "flow"),
+            transport,
+            tls,
+            fingerprint: get_non_empty_query_value(&query, "fp"),
+            reality,
+        })
+    }
+}
+impl super::GetNodeName for VlessNode {
+    fn get_name(&self) -> Option<&String> {
+        self.remarks.as_ref()
+    }
+
+    fn get_server(&'_ self) -> &'_ String {
+        &self.server
+    }
+
+    fn get_port(&self) -> u16 {
+        self.port
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RealityOptions {
+    pub public_key: String,
+    pub short_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_vless_reality_link() {
+        let link = Url::parse("vless://b831381d-6324-4d53-ad4f-8cda48b30811@192.168.100.1:443?encryption=none&flow=xtls-rprx-vision&security=reality&sni=www.microsoft.com&fp=chrome&pbk=Z84J2IelR9ch3k8VtlVhhs5ycBUlXA7wHBWcBrjqnAw&sid=6ba85179e30d4fc2&type=tcp&headerType=none#Example%20VLESS").unwrap();
+        let node = VlessNode {
+            remarks: Some(String::from("Example VLESS")),
+            server: String::from("192.168.100.1"),
+            port: 443,
+            uuid: Uuid::parse_str("b831381d-6324-4d53-ad4f-8cda48b30811").unwrap(),
+            flow: Some(String::from("xtls-rprx-vision")),
+            transport: None,
+            tls: Some(TlsOptions {
+                sni: Some(String::from("www.microsoft.com")),
+                insecure: None,
+                alpn: None,
+            }),
+            fingerprint: Some(String::from("chrome")),
+            reality: Some(RealityOptions {
+                public_key: String::from("Z84J2IelR9ch3k8VtlVhhs5ycBUlXA7wHBWcBrjqnAw"),
+                short_id: Some(String::from("6ba85179e30d4fc2")),
+            }),
+        };
+        assert_eq!(VlessNode::from_url(&link).unwrap(), node);
+    }
+
+    #[test]
+    fn parse_vless_ws_tls_link() {
+        let link = Url::parse("vless://b831381d-6324-4d53-ad4f-8cda48b30811@example.com:443?security=tls&sni=example.com&alpn=h2%2Chttp%2F1.1&type=ws&host=cdn.example.com&path=%2Fvless").unwrap();
+        let node = VlessNode {
+            remarks: None,
+            server: String::from("example.com"),
+            port: 443,
+            uuid: Uuid::parse_str("b831381d-6324-4d53-ad4f-8cda48b30811").unwrap(),
+            flow: None,
+            transport: Some(Transport::Ws {
+                path: Some(String::from("/vless")),
+                host: Some(String::from("cdn.example.com")),
+            }),
+            tls: Some(TlsOptions {
+                sni: Some(String::from("example.com")),
+                insecure: None,
+                alpn: Some(vec![String::from("h2"), String::from("http/1.1")]),
+            }),
+            fingerprint: None,
+            reality: None,
+        };
+        assert_eq!(VlessNode::from_url(&link).unwrap(), node);
+    }
+}
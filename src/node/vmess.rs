@@ -0,0 +1,206 @@
+use anyhow::{anyhow, Context, Result};
+use base64_simd::{forgiving_decode_to_vec, URL_SAFE_NO_PAD as base64_url_no_pad};
+use log::trace;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr, PickFirst};
+use url::Url;
+use uuid::Uuid;
+
+use crate::node::common::{TlsOptions, Transport};
+
+/// The configuration of a VMess node.
+/// Reference: https://www.v2fly.org/en_US/v5/config/proxy/vmess.html
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VmessNode {
+    pub remarks: Option<String>,
+    pub server: String,
+    pub port: u16,
+    pub uuid: Uuid,
+    pub alter_id: Option<u16>,
+    pub security: Option<Security>,
+    pub transport: Option<Transport>,
+    pub tls: Option<TlsOptions>,
+}
+impl VmessNode {
+    /// Convert a VMess link to a VMess node.
+    /// Reference: [v2rayN share link format](https://github.com/2dust/v2rayN/wiki/Description-of-VMess-share-link)
+    /// ```
+    /// vmess://base64(json)
+    /// ```
+    pub fn from_url(url: &Url) -> Result<Self> {
+        let encoded_content = url
+            .as_str()
+            .strip_prefix("vmess://")
+            .ok_or_else(|| anyhow!("`{}` is not a VMess link.", url))?;
+        let decoded_content_bytes = forgiving_decode_to_vec(encoded_content.as_bytes())
+            .or_else(|_| base64_url_no_pad.decode_to_vec(encoded_content))
+            .context("failed to decode base64 for the VMess link")?;
+        trace!(
+            "decoded VMess link: {}",
+            String::from_utf8_lossy(&decoded_content_bytes)
+        );
+
+        let share_link: VmessShareLink = serde_json::from_slice(&decoded_content_bytes)
+            .context("failed to parse the VMess link as JSON")?;
+
+        let security = share_link
+            .scy
+            .filter(|scy| !scy.is_empty())
+            .map(|scy| {
+                Security::from_alias(&scy)
+                    .ok_or_else(|| anyhow!("Unknown security `{}` in VMess link `{}`", scy, url))
+            })
+            .transpose()?;
+
+        let transport = Transport::from_share_link_params(
+            share_link.net.as_deref().unwrap_or_default(),
+            non_empty(share_link.host),
+            non_empty(share_link.path.clone()),
+            non_empty(share_link.path),
+        )
+        .context("failed to parse the transport of the VMess link")?;
+
+        let tls = if matches!(share_link.tls.as_deref(), Some("tls")) {
+            Some(TlsOptions {
+                sni: non_empty(share_link.sni),
+                insecure: None,
+                alpn: non_empty(share_link.alpn)
+                    .map(|alpn| alpn.split(',').map(String::from).collect()),
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            remarks: non_empty(share_link.ps),
+            server: share_link.add,
+            port: share_link.port,
+            uuid: share_link.id,
+            alter_id: share_link.aid,
+            security,
+            transport,
+            tls,
+        })
+    }
+}
+impl super::GetNodeName for VmessNode {
+    fn get_name(&self) -> Option<&String> {
+        self.remarks.as_ref()
+    }
+
+    fn get_server(&'_ self) -> &'_ String {
+        &self.server
+    }
+
+    fn get_port(&self) -> u16 {
+        self.port
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Security {
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "aes-128-gcm")]
+    Aes128Gcm,
+    #[serde(rename = "chacha20-poly1305")]
+    Chacha20Poly1305,
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "zero")]
+    Zero,
+}
+impl Security {
+    pub fn get_alias(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Aes128Gcm => "aes-128-gcm",
+            Self::Chacha20Poly1305 => "chacha20-poly1305",
+            Self::None => "none",
+            Self::Zero => "zero",
+        }
+    }
+
+    pub fn from_alias(alias: &str) -> Option<Self> {
+        match alias {
+            "auto" => Some(Self::Auto),
+            "aes-128-gcm" => Some(Self::Aes128Gcm),
+            "chacha20-poly1305" => Some(Self::Chacha20Poly1305),
+            "none" => Some(Self::None),
+            "zero" => Some(Self::Zero),
+
+            _ => None,
+        }
+    }
+}
+
+/// The JSON object inside a VMess link.
+/// Some clients use strings for numeric fields, so both forms are accepted.
+#[serde_as]
+#[derive(Debug, Deserialize)]
+struct VmessShareLink {
+    ps: Option<String>,
+    add: String,
+    #[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+    port: u16,
+    id: Uuid,
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[serde(default)]
+    aid: Option<u16>,
+    scy: Option<String>,
+    net: Option<String>,
+    host: Option<String>,
+    path: Option<String>,
+    tls: Option<String>,
+    sni: Option<String>,
+    alpn: Option<String>,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_vmess_link() {
+        let link = Url::parse("vmess://eyJ2IjoiMiIsInBzIjoiRXhhbXBsZSBWTWVzcyIsImFkZCI6ImV4YW1wbGUuY29tIiwicG9ydCI6IjQ0MyIsImlkIjoiYjgzMTM4MWQtNjMyNC00ZDUzLWFkNGYtOGNkYTQ4YjMwODExIiwiYWlkIjoiMCIsInNjeSI6ImF1dG8iLCJuZXQiOiJ3cyIsInR5cGUiOiJub25lIiwiaG9zdCI6ImNkbi5leGFtcGxlLmNvbSIsInBhdGgiOiIvcmF5IiwidGxzIjoidGxzIiwic25pIjoiZXhhbXBsZS5jb20iLCJhbHBuIjoiIn0=").unwrap();
+        let node = VmessNode {
+            remarks: Some(String::from("Example VMess")),
+            server: String::from("example.com"),
+            port: 443,
+            uuid: Uuid::parse_str("b831381d-6324-4d53-ad4f-8cda48b30811").unwrap(),
+            alter_id: Some(0),
+            security: Some(Security::Auto),
+            transport: Some(Transport::Ws {
+                path: Some(String::from("/ray")),
+                host: Some(String::from("cdn.example.com")),
+            }),
+            tls: Some(TlsOptions {
+                sni: Some(String::from("example.com")),
+                insecure: None,
+                alpn: None,
+            }),
+        };
+        assert_eq!(VmessNode::from_url(&link).unwrap(), node);
+    }
+
+    #[test]
+    fn parse_vmess_link_with_numeric_fields() {
+        let link = Url::parse("vmess://eyJ2IjoiMiIsInBzIjoiIiwiYWRkIjoiMTkyLjE2OC4xMDAuMSIsInBvcnQiOjEwMDg2LCJpZCI6ImI4MzEzODFkLTYzMjQtNGQ1My1hZDRmLThjZGE0OGIzMDgxMSIsImFpZCI6NjQsIm5ldCI6InRjcCIsInR5cGUiOiJub25lIiwiaG9zdCI6IiIsInBhdGgiOiIiLCJ0bHMiOiIifQ==").unwrap();
+        let node = VmessNode {
+            remarks: None,
+            server: String::from("192.168.100.1"),
+            port: 10086,
+            uuid: Uuid::parse_str("b831381d-6324-4d53-ad4f-8cda48b30811").unwrap(),
+            alter_id: Some(64),
+            security: None,
+            transport: None,
+            tls: None,
+        };
+        assert_eq!(VmessNode::from_url(&link).unwrap(), node);
+    }
+}
@@ -1,6 +1,13 @@
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
+use anyhow::{anyhow, Context, Result};
+use base64_simd::STANDARD as base64;
+use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::node::common::get_non_empty_query_value;
 
 /// The configuration of a Hysteria node.
 /// Reference: https://www.wireguard.com/papers/wireguard.pdf
@@ -37,6 +44,199 @@ pub struct WireguardNode {
     /// However, in some modified implementations (e.g. Cluodflare WARP),
     /// this field is required.
     pub reserved: Option<[u8; 3]>,
+
+    /// The IP ranges routed to the peer, e.g. `0.0.0.0/0`.
+    /// All IPs are routed if not set.
+    pub allowed_ips: Option<Vec<String>>,
+}
+impl WireguardNode {
+    /// Convert a WireGuard link to a WireGuard node.
+    /// ```
+    /// wireguard://private_key@host:port?publickey=...&address=172.16.0.2/32,fd01::2/128&presharedkey=...&reserved=1,2,3#remarks
+    /// ```
+    pub fn from_url(url: &Url) -> Result<Self> {
+        let server = url
+            .host_str()
+            .ok_or_else(|| anyhow!("WireGuard link `{}` does not contain server.", url))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+
+        let port = url
+            .port()
+            .ok_or_else(|| anyhow!("WireGuard link `{}` does not contain port.", url))?;
+
+        let private_key = percent_decode_str(url.username())
+            .decode_utf8_lossy()
+            .to_string();
+        if private_key.is_empty() {
+            return Err(anyhow!(
+                "WireGuard link `{}` does not contain private key.",
+                url
+            ));
+        }
+
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        let public_key = get_non_empty_query_value(&query, "publickey")
+            .or_else(|| get_non_empty_query_value(&query, "public_key"))
+            .ok_or_else(|| anyhow!("WireGuard link `{}` does not contain public key.", url))?;
+
+        let (ip, ipv6) = match get_non_empty_query_value(&query, "address")
+            .or_else(|| get_non_empty_query_value(&query, "ip"))
+        {
+            Some(address) => parse_addresses(&address)
+                .with_context(|| format!("invalid address in WireGuard link `{url}`"))?,
+            None => (None, None),
+        };
+
+        let reserved = get_non_empty_query_value(&query, "reserved")
+            .map(|reserved| parse_reserved(&reserved))
+            .transpose()
+            .with_context(|| format!("invalid reserved field in WireGuard link `{url}`"))?;
+
+        let remarks = url
+            .fragment()
+            .map(|remarks| percent_decode_str(remarks).decode_utf8_lossy().to_string());
+
+        Ok(Self {
+            remarks,
+            server,
+            port,
+            ip,
+            ipv6,
+            private_key,
+            public_key,
+            pre_shared_key: get_non_empty_query_value(&query, "presharedkey")
+                .or_else(|| get_non_empty_query_value(&query, "pre_shared_key")),
+            reserved,
+            allowed_ips: None,
+        })
+    }
+
+    /// Convert a wg-quick configuration file to WireGuard nodes,
+    /// one node for each `[Peer]` section.
+    /// Reference: https://man7.org/linux/man-pages/man8/wg-quick.8.html
+    /// ```ini
+    /// [Interface]
+    /// PrivateKey = ...
+    /// Address = 172.16.0.2/32, fd01::2/128
+    ///
+    /// [Peer]
+    /// PublicKey = ...
+    /// Endpoint = engage.cloudflareclient.com:2408
+    /// ```
+    pub fn from_wg_quick_conf(conf: &str) -> Result<Vec<Self>> {
+        let mut private_key = None;
+        let mut ip = None;
+        let mut ipv6 = None;
+        let mut interface_reserved = None;
+        let mut peers: Vec<HashMap<String, String>> = Vec::new();
+
+        let mut in_peer_section = false;
+        for (line_number, line) in conf.lines().enumerate() {
+            let line = line.split(['#', ';']).next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') && line.ends_with(']') {
+                let section = line[1..line.len() - 1].trim().to_ascii_lowercase();
+                in_peer_section = match section.as_str() {
+                    "interface" => false,
+                    "peer" => {
+                        peers.push(HashMap::new());
+                        true
+                    }
+                    _ => return Err(anyhow!("unknown section `{}`", line)),
+                };
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| anyhow!("invalid line {}: `{}`", line_number + 1, line))?;
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            if in_peer_section {
+                if let Some(peer) = peers.last_mut() {
+                    peer.insert(key, value.to_string());
+                }
+            } else {
+                match key.as_str() {
+                    "privatekey" => private_key = Some(value.to_string()),
+                    "address" => {
+                        let (v4, v6) = parse_addresses(value)?;
+                        ip = ip.or(v4);
+                        ipv6 = ipv6.or(v6);
+                    }
+                    "reserved" => interface_reserved = Some(parse_reserved(value)?),
+                    // Other fields (e.g. `DNS`, `MTU`) are not used by proxy clients.
+                    _ => {}
+                }
+            }
+        }
+
+        let private_key =
+            private_key.ok_or_else(|| anyhow!("`PrivateKey` is missing in `[Interface]`"))?;
+
+        if peers.is_empty() {
+            return Err(anyhow!("no `[Peer]` section found"));
+        }
+
+        peers
+            .into_iter()
+            .map(|peer| {
+                let public_key = peer
+                    .get("publickey")
+                    .cloned()
+                    .ok_or_else(|| anyhow!("`PublicKey` is missing in `[Peer]`"))?;
+
+                let endpoint = peer
+                    .get("endpoint")
+                    .ok_or_else(|| anyhow!("`Endpoint` is missing in `[Peer]`"))?;
+                let (server, port) = endpoint
+                    .rsplit_once(':')
+                    .ok_or_else(|| anyhow!("invalid endpoint `{}`", endpoint))?;
+                let port = port
+                    .parse()
+                    .with_context(|| format!("invalid port in endpoint `{endpoint}`"))?;
+
+                let reserved = peer
+                    .get("reserved")
+                    .map(|reserved| parse_reserved(reserved))
+                    .transpose()?
+                    .or(interface_reserved);
+
+                Ok(Self {
+                    remarks: None,
+                    server: server
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .to_string(),
+                    port,
+                    ip,
+                    ipv6,
+                    private_key: private_key.clone(),
+                    public_key,
+                    pre_shared_key: peer.get("presharedkey").cloned(),
+                    reserved,
+                    allowed_ips: peer
+                        .get("allowedips")
+                        .map(|allowed_ips| parse_allowed_ips(allowed_ips)),
+                })
+            })
+            .collect()
+    }
+
+    /// Get the IP ranges routed to the peer, which defaults to all IPs.
+    pub fn get_allowed_ips(&self) -> Vec<&str> {
+        match &self.allowed_ips {
+            Some(allowed_ips) => allowed_ips.iter().map(String::as_str).collect(),
+            None => vec!["0.0.0.0/0", "::/0"],
+        }
+    }
 }
 impl super::GetNodeName for WireguardNode {
     fn get_name(&self) -> Option<&String> {
@@ -51,3 +251,116 @@ impl super::GetNodeName for WireguardNode {
         self.port
     }
 }
+
+/// Parse a comma separated list of addresses (with optional CIDR suffixes)
+/// into the first IPv4 address and the first IPv6 address.
+pub fn parse_addresses(addresses: &str) -> Result<(Option<Ipv4Addr>, Option<Ipv6Addr>)> {
+    let mut ip = None;
+    let mut ipv6 = None;
+
+    for address in addresses.split(',') {
+        let address = address.trim();
+        if address.is_empty() {
+            continue;
+        }
+
+        let addr = address.split('/').next().unwrap_or_default();
+        match addr
+            .parse::<IpAddr>()
+            .with_context(|| format!("invalid address `{address}`"))?
+        {
+            IpAddr::V4(v4) => ip = ip.or(Some(v4)),
+            IpAddr::V6(v6) => ipv6 = ipv6.or(Some(v6)),
+        }
+    }
+
+    Ok((ip, ipv6))
+}
+
+/// Parse a comma separated list of IP ranges, e.g. `0.0.0.0/0, ::/0`.
+pub fn parse_allowed_ips(allowed_ips: &str) -> Vec<String> {
+    allowed_ips
+        .split(',')
+        .map(str::trim)
+        .filter(|allowed_ip| !allowed_ip.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Parse the reserved field, which is either a list of 3 numbers (e.g. `1, 2, 3`)
+/// or a base64 string of 3 bytes (e.g. the `client_id` of Cloudflare WARP).
+pub fn parse_reserved(reserved: &str) -> Result<[u8; 3]> {
+    let bytes = if reserved.contains(',') {
+        reserved
+            .split(',')
+            .map(|n| n.trim().parse::<u8>())
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("invalid reserved `{reserved}`"))?
+    } else {
+        base64
+            .decode_to_vec(reserved)
+            .with_context(|| format!("invalid reserved `{reserved}`"))?
+    };
+
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("reserved `{}` should contain exactly 3 bytes", reserved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_wireguard_link() {
+        let link = Url::parse("wireguard://yAnz5TF%2BlXXJte14tji3zlMNq%2BhdZvsXnrqlLzVrd0k%3D@162.159.192.1:2408?publickey=bmXOC%2BF1FxEMF9dyiK2H5%2F1SUtzH0JuVo51h2wPfgyo%3D&address=172.16.0.2%2F32%2C2606%3A4700%3A110%3A8a36%3A%3A2%2F128&reserved=1%2C2%2C3#Example%20WireGuard").unwrap();
+        let node = WireguardNode {
+            remarks: Some(String::from("Example WireGuard")),
+            server: String::from("162.159.192.1"),
+            port: 2408,
+            ip: Some(Ipv4Addr::new(172, 16, 0, 2)),
+            ipv6: Some("2606:4700:110:8a36::2".parse().unwrap()),
+            private_key: String::from("yAnz5TF+lXXJte14tji3zlMNq+hdZvsXnrqlLzVrd0k="),
+            public_key: String::from("bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo="),
+            pre_shared_key: None,
+            reserved: Some([1, 2, 3]),
+            allowed_ips: None,
+        };
+        assert_eq!(WireguardNode::from_url(&link).unwrap(), node);
+    }
+
+    #[test]
+    fn parse_wg_quick_conf() {
+        let conf = r#"
+[Interface]
+PrivateKey = yAnz5TF+lXXJte14tji3zlMNq+hdZvsXnrqlLzVrd0k=
+Address = 172.16.0.2/32
+Address = 2606:4700:110:8a36::2/128
+DNS = 1.1.1.1
+# WARP client_id
+Reserved = AQID
+
+[Peer]
+PublicKey = bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo=
+PresharedKey = ZmFrZS1wcmUtc2hhcmVkLWtleS0xMjM0NTY3ODkwYWI=
+AllowedIPs = 0.0.0.0/0, 2606:4700::/32
+Endpoint = engage.cloudflareclient.com:2408
+"#;
+        let node = WireguardNode {
+            remarks: None,
+            server: String::from("engage.cloudflareclient.com"),
+            port: 2408,
+            ip: Some(Ipv4Addr::new(172, 16, 0, 2)),
+            ipv6: Some("2606:4700:110:8a36::2".parse().unwrap()),
+            private_key: String::from("yAnz5TF+lXXJte14tji3zlMNq+hdZvsXnrqlLzVrd0k="),
+            public_key: String::from("bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo="),
+            pre_shared_key: Some(String::from("ZmFrZS1wcmUtc2hhcmVkLWtleS0xMjM0NTY3ODkwYWI=")),
+            reserved: Some([1, 2, 3]),
+            allowed_ips: Some(vec![
+                String::from("0.0.0.0/0"),
+                String::from("2606:4700::/32"),
+            ]),
+        };
+        assert_eq!(WireguardNode::from_wg_quick_conf(conf).unwrap(), vec![node]);
+    }
+}
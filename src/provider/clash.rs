@@ -1,14 +1,29 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use bytes::Bytes;
-use http::Uri;
+use log::warn;
 use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use uuid::Uuid;
 
+use crate::node::common::{ServerPort, TlsOptions};
+use crate::node::hysteria::{Protocol as HysteriaProtocol, Speed as HysteriaSpeed};
+use crate::node::hysteria2::{Obfuscation as Hysteria2Obfuscation, Speed as Hysteria2Speed};
+use crate::node::snell::SnellObfsOpts;
 use crate::node::ss::Method as SsMethod;
-use crate::node::{Node, SsNode, SsrNode};
-use crate::template::adaptors::clash::ClashProxy;
+use crate::node::tuic::{
+    CongestionControl as TuicCongestionControl, UdpRelayMode as TuicUdpRelayMode,
+};
+use crate::node::vless::RealityOptions;
+use crate::node::vmess::Security as VmessSecurity;
+use crate::node::wireguard::{parse_addresses, parse_reserved};
+use crate::node::{
+    HttpNode, Hysteria2Node, HysteriaNode, Node, SnellNode, Socks5Node, SsNode, SsrNode,
+    TrojanNode, TuicNode, VlessNode, VmessNode, WireguardNode,
+};
+use crate::template::adaptors::clash::{ClashProxy, ClashTransport};
 
-use super::{CommonProviderOptions, Provider};
+use super::{CommonProviderOptions, Provider, ProviderSource};
 
 /// SSR subscription.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,9 +32,10 @@ pub struct Clash {
     /// Name of the Clash subscription.
     pub name: Option<String>,
 
-    /// URL of the Clash subscription.
-    #[serde(with = "http_serde::uri")]
-    pub url: Uri,
+    /// Where to load the Clash subscription from,
+    /// which could be a `url`, a local `path` or the inline `content`.
+    #[serde(flatten)]
+    pub source: ProviderSource,
 
     /// Common provider options.
     #[serde(flatten)]
@@ -32,88 +48,1021 @@ impl Provider for Clash {
         self.name.as_ref()
     }
 
-    fn get_url(&self) -> &Uri {
-        &self.url
+    fn get_source(&self) -> Option<&ProviderSource> {
+        Some(&self.source)
+    }
+
+    fn get_source_mut(&mut self) -> Option<&mut ProviderSource> {
+        Some(&mut self.source)
+    }
+
+    fn get_options(&self) -> &CommonProviderOptions {
+        &self.options
     }
 
-    fn set_url(&mut self, url: Uri) {
-        self.url = url;
+    fn get_options_mut(&mut self) -> &mut CommonProviderOptions {
+        &mut self.options
     }
 
     fn parse_nodes_from_content(&self, content: Bytes) -> Result<Vec<Node>> {
-        let clash_config: ClashConfiguration = serde_yaml::from_slice(&content)
-            .context("failed to parse the provider content as Clash configuration yaml")?;
-        Ok(clash_config
-            .proxies
-            .into_iter()
-            .filter_map(|ipnoupn| match ipnoupn {
-                ImplementedProxyNodeOrUnknownProxyNode::Implemented(clash_proxy) => {
-                    Some(clash_proxy)
+        parse_nodes(content, &self.get_display_name())
+    }
+}
+
+/// Parse the content of a Clash configuration.
+/// `provider_name` is only used in log messages.
+pub(super) fn parse_nodes(content: Bytes, provider_name: &str) -> Result<Vec<Node>> {
+    let clash_config: ClashConfiguration = serde_yaml::from_slice(&content)
+        .context("failed to parse the provider content as Clash configuration yaml")?;
+    Ok(clash_config
+        .proxies
+        .into_iter()
+        .filter_map(|ipnoupn| {
+            let node =
+                match ipnoupn {
+                    ImplementedProxyNodeOrUnknownProxyNode::Implemented(clash_proxy) => {
+                        clash_proxy_to_node(clash_proxy)
+                    }
+                    ImplementedProxyNodeOrUnknownProxyNode::ImplementedClashMeta(
+                        clash_meta_proxy,
+                    ) => clash_meta_only_proxy_to_node(clash_meta_proxy, provider_name),
+                    ImplementedProxyNodeOrUnknownProxyNode::Unknown(proxy) => {
+                        warn!(
+                        "unsupported or invalid proxy `{}` of type `{}` in provider `{}`, skip it",
+                        proxy.get("name").and_then(Value::as_str).unwrap_or_default(),
+                        proxy.get("type").and_then(Value::as_str).unwrap_or_default(),
+                        provider_name
+                    );
+                        return None;
+                    }
+                };
+
+            match node {
+                Ok(node) => Some(node),
+                Err(err) => {
+                    warn!(
+                        "failed to parse proxy in provider `{}`: {:#}, skip it",
+                        provider_name, err
+                    );
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+fn clash_proxy_to_node(proxy: ClashProxy) -> Result<Node> {
+    match proxy {
+        ClashProxy::Ss {
+            name,
+            server,
+            port,
+            cipher,
+            password,
+            udp,
+            plugin,
+        } => {
+            let method = SsMethod::from_alias(&cipher)
+                .ok_or_else(|| anyhow!("Unknown cipher `{}` in proxy `{}`", cipher, name))?;
+
+            Ok(Node::Ss(Box::new(SsNode {
+                id: None,
+                remarks: Some(name),
+                server,
+                server_port: port,
+                password,
+                method,
+                udp,
+                udp_over_tcp: None,
+                plugin: plugin.map(Into::into),
+            })))
+        }
+        ClashProxy::Ssr {
+            name,
+            server,
+            port,
+            cipher,
+            password,
+            obfs,
+            obfs_param,
+            protocol,
+            protocol_param,
+            udp: _,
+        } => Ok(Node::Ssr(Box::new(SsrNode {
+            remarks: Some(name),
+            server,
+            server_port: port,
+            password,
+            method: cipher,
+            protocol,
+            protocol_param,
+            obfs,
+            obfs_param,
+            udpport: None,
+            uot: None,
+        }))),
+        ClashProxy::Vmess {
+            name,
+            server,
+            port,
+            uuid,
+            alter_id,
+            cipher,
+            udp: _,
+            tls,
+            skip_cert_verify,
+            servername,
+            transport,
+        } => Ok(Node::Vmess(Box::new(VmessNode {
+            uuid: Uuid::parse_str(&uuid)
+                .with_context(|| format!("invalid uuid `{uuid}` of proxy `{name}`"))?,
+            security: Some(
+                VmessSecurity::from_alias(&cipher)
+                    .ok_or_else(|| anyhow!("Unknown cipher `{}` in proxy `{}`", cipher, name))?,
+            ),
+            remarks: Some(name),
+            server,
+            port,
+            alter_id: Some(alter_id),
+            transport: transport.map(Into::into),
+            tls: if matches!(tls, Some(true)) {
+                Some(TlsOptions {
+                    sni: servername,
+                    insecure: skip_cert_verify,
+                    alpn: None,
+                })
+            } else {
+                None
+            },
+        }))),
+        ClashProxy::Trojan {
+            name,
+            server,
+            port,
+            password,
+            udp: _,
+            sni,
+            alpn,
+            skip_cert_verify,
+            transport,
+        } => Ok(Node::Trojan(Box::new(TrojanNode {
+            remarks: Some(name),
+            server,
+            port,
+            password,
+            transport: transport.map(Into::into),
+            tls: TlsOptions {
+                sni,
+                insecure: skip_cert_verify,
+                alpn,
+            },
+        }))),
+        ClashProxy::Socks5 {
+            name,
+            server,
+            port,
+            username,
+            password,
+            tls,
+            skip_cert_verify,
+            udp: _,
+        } => Ok(Node::Socks5(Box::new(Socks5Node {
+            remarks: Some(name),
+            server,
+            port,
+            username,
+            password,
+            tls: if matches!(tls, Some(true)) {
+                Some(TlsOptions {
+                    sni: None,
+                    insecure: skip_cert_verify,
+                    alpn: None,
+                })
+            } else {
+                None
+            },
+        }))),
+        ClashProxy::Http {
+            name,
+            server,
+            port,
+            username,
+            password,
+            tls,
+            skip_cert_verify,
+            sni,
+        } => Ok(Node::Http(Box::new(HttpNode {
+            remarks: Some(name),
+            server,
+            port,
+            username,
+            password,
+            tls: if matches!(tls, Some(true)) {
+                Some(TlsOptions {
+                    sni,
+                    insecure: skip_cert_verify,
+                    alpn: None,
+                })
+            } else {
+                None
+            },
+        }))),
+        ClashProxy::Snell {
+            name,
+            server,
+            port,
+            psk,
+            version,
+            obfs_opts,
+        } => Ok(Node::Snell(Box::new(SnellNode {
+            version: version
+                .map(SnellNode::check_version)
+                .transpose()
+                .with_context(|| format!("invalid `version` of proxy `{name}`"))?,
+            remarks: Some(name),
+            server,
+            port,
+            psk,
+            obfs: obfs_opts.and_then(|obfs_opts| {
+                Some(SnellObfsOpts {
+                    mode: obfs_opts.mode?.into(),
+                    host: obfs_opts.host,
+                })
+            }),
+        }))),
+    }
+}
+
+fn clash_meta_only_proxy_to_node(proxy: ClashMetaOnlyProxy, provider_name: &str) -> Result<Node> {
+    match proxy {
+        ClashMetaOnlyProxy::Vless {
+            name,
+            server,
+            port,
+            uuid,
+            flow,
+            tls,
+            servername,
+            skip_cert_verify,
+            alpn,
+            client_fingerprint,
+            reality_opts,
+            transport,
+        } => Ok(Node::Vless(Box::new(VlessNode {
+            uuid: Uuid::parse_str(&uuid)
+                .with_context(|| format!("invalid uuid `{uuid}` of proxy `{name}`"))?,
+            remarks: Some(name),
+            server,
+            port,
+            flow,
+            transport: transport.map(Into::into),
+            tls: if matches!(tls, Some(true)) {
+                Some(TlsOptions {
+                    sni: servername,
+                    insecure: skip_cert_verify,
+                    alpn,
+                })
+            } else {
+                None
+            },
+            fingerprint: client_fingerprint,
+            reality: reality_opts.map(|reality_opts| RealityOptions {
+                public_key: reality_opts.public_key,
+                short_id: reality_opts.short_id,
+            }),
+        }))),
+        ClashMetaOnlyProxy::Tuic {
+            name,
+            server,
+            port,
+            uuid,
+            password,
+            congestion_controller,
+            udp_relay_mode,
+            sni,
+            alpn,
+            skip_cert_verify,
+        } => {
+            let congestion_control = congestion_controller
+                .map(|congestion_controller| {
+                    TuicCongestionControl::from_alias(&congestion_controller).ok_or_else(|| {
+                        anyhow!(
+                            "Unknown congestion control `{}` in proxy `{}`",
+                            congestion_controller,
+                            name
+                        )
+                    })
+                })
+                .transpose()?;
+
+            let udp_relay_mode = udp_relay_mode
+                .map(|udp_relay_mode| {
+                    TuicUdpRelayMode::from_alias(&udp_relay_mode).ok_or_else(|| {
+                        anyhow!(
+                            "Unknown UDP relay mode `{}` in proxy `{}`",
+                            udp_relay_mode,
+                            name
+                        )
+                    })
+                })
+                .transpose()?;
+
+            Ok(Node::Tuic(Box::new(TuicNode {
+                uuid: Uuid::parse_str(&uuid)
+                    .with_context(|| format!("invalid uuid `{uuid}` of proxy `{name}`"))?,
+                remarks: Some(name),
+                server,
+                port,
+                password,
+                congestion_control,
+                udp_relay_mode,
+                tls: TlsOptions {
+                    sni,
+                    insecure: skip_cert_verify,
+                    alpn,
+                },
+            })))
+        }
+        ClashMetaOnlyProxy::Hysteria {
+            name,
+            server,
+            port,
+            ports,
+            auth_str,
+            obfs,
+            alpn,
+            protocol,
+            up,
+            down,
+            sni,
+            skip_cert_verify,
+        } => Ok(Node::Hysteria(Box::new(HysteriaNode {
+            port: match ports {
+                Some(ports) => parse_ports(provider_name, &name, &ports)?,
+                None => ServerPort::Single(port),
+            },
+            protocol: protocol.and_then(|protocol| HysteriaProtocol::from_alias(&protocol)),
+            up: up.ok_or_else(|| anyhow!("proxy `{}` does not contain `up`", name))?,
+            down: down.ok_or_else(|| anyhow!("proxy `{}` does not contain `down`", name))?,
+            remarks: Some(name),
+            server,
+            obfs,
+            auth: auth_str,
+            tls: TlsOptions {
+                sni,
+                insecure: skip_cert_verify,
+                alpn,
+            },
+        }))),
+        ClashMetaOnlyProxy::Hysteria2 {
+            name,
+            server,
+            port,
+            ports,
+            password,
+            obfs,
+            obfs_password,
+            up,
+            down,
+            sni,
+            alpn,
+            skip_cert_verify,
+        } => Ok(Node::Hysteria2(Box::new(Hysteria2Node {
+            port: match ports {
+                Some(ports) => parse_ports(provider_name, &name, &ports)?,
+                None => ServerPort::Single(port),
+            },
+            obfs: match obfs.as_deref() {
+                None => None,
+                Some("salamander") => Some(Hysteria2Obfuscation::Salamander {
+                    password: obfs_password.ok_or_else(|| {
+                        anyhow!("proxy `{}` does not contain `obfs-password`", name)
+                    })?,
+                }),
+                Some(obfs) => {
+                    return Err(anyhow!("Unknown obfs type `{}` in proxy `{}`", obfs, name))
                 }
-                ImplementedProxyNodeOrUnknownProxyNode::Unknown(_) => None,
-            })
-            .filter_map(|proxy| match proxy {
-                ClashProxy::Ss {
-                    name,
-                    server,
-                    port,
-                    cipher,
-                    password,
-                    udp,
-                    plugin,
-                } => {
-                    let method = SsMethod::from_alias(&cipher)?;
-
-                    Some(Node::Ss(Box::new(SsNode {
-                        id: None,
-                        remarks: Some(name),
-                        server,
-                        server_port: port,
-                        password,
-                        method,
-                        udp,
-                        udp_over_tcp: None,
-                        plugin: plugin.map(Into::into),
-                    })))
+            },
+            remarks: Some(name),
+            server,
+            auth: password,
+            up,
+            down,
+            tls: TlsOptions {
+                sni,
+                insecure: skip_cert_verify,
+                alpn,
+            },
+        }))),
+        ClashMetaOnlyProxy::Wireguard {
+            name,
+            server,
+            port,
+            ip,
+            ipv6,
+            private_key,
+            public_key,
+            pre_shared_key,
+            reserved,
+            allowed_ips,
+            peers,
+        } => {
+            // A WireGuard node holds a single peer, so multiple `peers` are not supported.
+            let mut peers = peers.unwrap_or_default().into_iter();
+            let peer = match (peers.next(), peers.next()) {
+                (Some(peer), None) => peer,
+                (Some(_), Some(_)) => {
+                    return Err(anyhow!(
+                        "proxy `{}` contains multiple peers, which is not supported",
+                        name
+                    ))
                 }
-                ClashProxy::Ssr {
-                    name,
-                    server,
-                    port,
-                    cipher,
-                    password,
-                    obfs,
-                    obfs_param,
-                    protocol,
-                    protocol_param,
-                    udp: _,
-                } => Some(Node::Ssr(Box::new(SsrNode {
-                    remarks: Some(name),
-                    server,
-                    server_port: port,
-                    password,
-                    method: cipher,
-                    protocol,
-                    protocol_param,
-                    obfs,
-                    obfs_param,
-                    udpport: None,
-                    uot: None,
-                }))),
-            })
-            .collect())
+                (None, _) => ClashMetaWireguardPeer {
+                    server: server
+                        .ok_or_else(|| anyhow!("proxy `{}` does not contain `server`", name))?,
+                    port: port
+                        .ok_or_else(|| anyhow!("proxy `{}` does not contain `port`", name))?,
+                    public_key: public_key
+                        .ok_or_else(|| anyhow!("proxy `{}` does not contain `public-key`", name))?,
+                    pre_shared_key,
+                    reserved,
+                    allowed_ips,
+                },
+            };
+
+            Ok(Node::Wireguard(WireguardNode {
+                ip: ip
+                    .map(|ip| parse_addresses(&ip))
+                    .transpose()
+                    .with_context(|| format!("invalid `ip` of proxy `{name}`"))?
+                    .and_then(|(ip, _)| ip),
+                ipv6: ipv6
+                    .map(|ipv6| parse_addresses(&ipv6))
+                    .transpose()
+                    .with_context(|| format!("invalid `ipv6` of proxy `{name}`"))?
+                    .and_then(|(_, ipv6)| ipv6),
+                reserved: match peer.reserved {
+                    Some(ClashMetaWireguardReserved::Bytes(reserved)) => Some(reserved),
+                    Some(ClashMetaWireguardReserved::String(reserved)) => Some(
+                        parse_reserved(&reserved)
+                            .with_context(|| format!("invalid `reserved` of proxy `{name}`"))?,
+                    ),
+                    None => None,
+                },
+                remarks: Some(name),
+                server: peer.server,
+                port: peer.port,
+                private_key,
+                public_key: peer.public_key,
+                pre_shared_key: peer.pre_shared_key,
+                allowed_ips: peer.allowed_ips,
+            }))
+        }
     }
 }
 
+/// Parse the port hopping `ports` of Clash.Meta, e.g. `20000-50000` or `443,20000-50000`.
+/// Only the first port or range is used, as a node holds a single one.
+fn parse_ports(provider_name: &str, name: &str, ports: &str) -> Result<ServerPort> {
+    let mut ports_iter = ports.split(',');
+    let port = ServerPort::from_port_str(ports_iter.next().unwrap_or_default())
+        .with_context(|| format!("invalid `ports` `{ports}` of proxy `{name}`"))?;
+
+    if ports_iter.next().is_some() {
+        warn!(
+            "proxy `{}` in provider `{}` hops between ports `{}`, only `{}` is used",
+            name,
+            provider_name,
+            ports,
+            ports.split(',').next().unwrap_or_default().trim()
+        );
+    }
+
+    Ok(port)
+}
+
 // TODO: Remove this after implemented all types of nodes.
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 enum ImplementedProxyNodeOrUnknownProxyNode {
     Implemented(ClashProxy),
-    Unknown(()),
+    ImplementedClashMeta(ClashMetaOnlyProxy),
+    Unknown(Value),
+}
+
+/// Proxies which are only supported by Clash.Meta (mihomo).
+/// Reference: https://wiki.metacubex.one/en/config/proxies/
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum ClashMetaOnlyProxy {
+    #[serde(rename = "vless", rename_all = "kebab-case")]
+    Vless {
+        name: String,
+        server: String,
+        port: u16,
+        uuid: String,
+        flow: Option<String>,
+        tls: Option<bool>,
+        servername: Option<String>,
+        skip_cert_verify: Option<bool>,
+        alpn: Option<Vec<String>>,
+        client_fingerprint: Option<String>,
+        reality_opts: Option<ClashMetaRealityOpts>,
+        #[serde(flatten)]
+        transport: Option<ClashTransport>,
+    },
+
+    #[serde(rename = "tuic", rename_all = "kebab-case")]
+    Tuic {
+        name: String,
+        server: String,
+        port: u16,
+        uuid: String,
+        password: String,
+        congestion_controller: Option<String>,
+        udp_relay_mode: Option<String>,
+        sni: Option<String>,
+        alpn: Option<Vec<String>>,
+        skip_cert_verify: Option<bool>,
+    },
+
+    #[serde(rename = "hysteria", rename_all = "kebab-case")]
+    Hysteria {
+        name: String,
+        server: String,
+        port: u16,
+        /// Port hopping ports, e.g. `20000-50000` or `443,20000-50000`.
+        ports: Option<String>,
+        auth_str: Option<String>,
+        obfs: Option<String>,
+        alpn: Option<Vec<String>>,
+        protocol: Option<String>,
+        up: Option<HysteriaSpeed>,
+        down: Option<HysteriaSpeed>,
+        sni: Option<String>,
+        skip_cert_verify: Option<bool>,
+    },
+
+    #[serde(rename = "hysteria2", rename_all = "kebab-case")]
+    Hysteria2 {
+        name: String,
+        server: String,
+        port: u16,
+        /// Port hopping ports, e.g. `20000-50000` or `443,20000-50000`.
+        ports: Option<String>,
+        password: Option<String>,
+        obfs: Option<String>,
+        obfs_password: Option<String>,
+        up: Option<Hysteria2Speed>,
+        down: Option<Hysteria2Speed>,
+        sni: Option<String>,
+        alpn: Option<Vec<String>>,
+        skip_cert_verify: Option<bool>,
+    },
+
+    /// The peer could be either inlined or in `peers`.
+    #[serde(rename = "wireguard", rename_all = "kebab-case")]
+    Wireguard {
+        name: String,
+        server: Option<String>,
+        port: Option<u16>,
+        ip: Option<String>,
+        ipv6: Option<String>,
+        private_key: String,
+        public_key: Option<String>,
+        pre_shared_key: Option<String>,
+        reserved: Option<ClashMetaWireguardReserved>,
+        allowed_ips: Option<Vec<String>>,
+        peers: Option<Vec<ClashMetaWireguardPeer>>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ClashMetaWireguardPeer {
+    server: String,
+    port: u16,
+    public_key: String,
+    pre_shared_key: Option<String>,
+    reserved: Option<ClashMetaWireguardReserved>,
+    allowed_ips: Option<Vec<String>>,
+}
+
+/// The reserved field of WireGuard in Clash.Meta,
+/// which could be either `[1, 2, 3]` or a base64 string.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ClashMetaWireguardReserved {
+    Bytes([u8; 3]),
+    String(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ClashMetaRealityOpts {
+    public_key: String,
+    short_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ClashConfiguration {
     proxies: Vec<ImplementedProxyNodeOrUnknownProxyNode>,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::node::common::Transport;
+    use crate::node::ss::ObfsType;
+
+    use super::*;
+
+    fn parse(content: &str) -> Vec<Node> {
+        let provider = Clash {
+            name: None,
+            source: ProviderSource::Content(content.to_string()),
+            options: CommonProviderOptions::default(),
+        };
+
+        provider
+            .parse_nodes_from_content(Bytes::from(content.to_string()))
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_vmess_proxies() {
+        let nodes = parse(
+            r#"
+proxies:
+  - name: "vmess"
+    type: vmess
+    server: example.com
+    port: 443
+    uuid: bf000d23-0752-40b4-affe-68f7707a9661
+    alterId: 0
+    cipher: auto
+    udp: true
+    tls: true
+    servername: example.com
+    network: ws
+    ws-opts:
+      path: /path
+      headers:
+        Host: cdn.example.com
+  - name: "vmess-with-invalid-uuid"
+    type: vmess
+    server: example.com
+    port: 443
+    uuid: not-a-uuid
+    alterId: 0
+    cipher: auto
+  - name: "vmess-with-unknown-cipher"
+    type: vmess
+    server: example.com
+    port: 443
+    uuid: bf000d23-0752-40b4-affe-68f7707a9661
+    alterId: 0
+    cipher: rc4
+"#,
+        );
+
+        assert_eq!(nodes.len(), 1);
+
+        let Node::Vmess(vmess_node) = &nodes[0] else {
+            panic!("expected a VMess node");
+        };
+        assert_eq!(
+            **vmess_node,
+            VmessNode {
+                remarks: Some(String::from("vmess")),
+                server: String::from("example.com"),
+                port: 443,
+                uuid: Uuid::parse_str("bf000d23-0752-40b4-affe-68f7707a9661").unwrap(),
+                alter_id: Some(0),
+                security: Some(VmessSecurity::Auto),
+                transport: Some(Transport::Ws {
+                    path: Some(String::from("/path")),
+                    host: Some(String::from("cdn.example.com")),
+                }),
+                tls: Some(TlsOptions {
+                    sni: Some(String::from("example.com")),
+                    insecure: None,
+                    alpn: None,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_snell_proxies() {
+        let nodes = parse(
+            r#"
+proxies:
+  - name: "snell"
+    type: snell
+    server: example.com
+    port: 44046
+    psk: yourpsk
+    version: 3
+    obfs-opts:
+      mode: http
+      host: bing.com
+  - name: "snell-with-unsupported-version"
+    type: snell
+    server: example.com
+    port: 44046
+    psk: yourpsk
+    version: 5
+"#,
+        );
+
+        assert_eq!(nodes.len(), 1);
+
+        let Node::Snell(snell_node) = &nodes[0] else {
+            panic!("expected a Snell node");
+        };
+        assert_eq!(
+            **snell_node,
+            SnellNode {
+                remarks: Some(String::from("snell")),
+                server: String::from("example.com"),
+                port: 44046,
+                psk: String::from("yourpsk"),
+                version: Some(3),
+                obfs: Some(SnellObfsOpts {
+                    mode: ObfsType::Http,
+                    host: Some(String::from("bing.com")),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_vless_and_tuic_proxies() {
+        let nodes = parse(
+            r#"
+proxies:
+  - name: "vless-reality"
+    type: vless
+    server: example.com
+    port: 443
+    uuid: bf000d23-0752-40b4-affe-68f7707a9661
+    network: tcp
+    tls: true
+    udp: true
+    flow: xtls-rprx-vision
+    servername: www.microsoft.com
+    reality-opts:
+      public-key: xYzPVvZmxrDrLBgE2JJmXg1S8oSl7vmdIBG6iNpvBWk
+      short-id: 10f897e2
+    client-fingerprint: chrome
+  - name: "tuic"
+    type: tuic
+    server: example.com
+    port: 10443
+    uuid: 00000000-0000-0000-0000-000000000001
+    password: PASSWORD_1
+    alpn: [h3]
+    disable-sni: true
+    reduce-rtt: true
+    request-timeout: 8000
+    udp-relay-mode: native
+    congestion-controller: bbr
+    sni: tuic.example.com
+  - name: "tuic-with-unknown-congestion-controller"
+    type: tuic
+    server: example.com
+    port: 10443
+    uuid: 00000000-0000-0000-0000-000000000001
+    password: PASSWORD_1
+    congestion-controller: unknown
+  - name: "tuic-with-unknown-udp-relay-mode"
+    type: tuic
+    server: example.com
+    port: 10443
+    uuid: 00000000-0000-0000-0000-000000000001
+    password: PASSWORD_1
+    udp-relay-mode: unknown
+"#,
+        );
+
+        assert_eq!(nodes.len(), 2);
+
+        let Node::Vless(vless_node) = &nodes[0] else {
+            panic!("expected a VLESS node");
+        };
+        assert_eq!(
+            **vless_node,
+            VlessNode {
+                remarks: Some(String::from("vless-reality")),
+                server: String::from("example.com"),
+                port: 443,
+                uuid: Uuid::parse_str("bf000d23-0752-40b4-affe-68f7707a9661").unwrap(),
+                flow: Some(String::from("xtls-rprx-vision")),
+                transport: None,
+                tls: Some(TlsOptions {
+                    sni: Some(String::from("www.microsoft.com")),
+                    insecure: None,
+                    alpn: None,
+                }),
+                fingerprint: Some(String::from("chrome")),
+                reality: Some(RealityOptions {
+                    public_key: String::from("xYzPVvZmxrDrLBgE2JJmXg1S8oSl7vmdIBG6iNpvBWk"),
+                    short_id: Some(String::from("10f897e2")),
+                }),
+            }
+        );
+
+        let Node::Tuic(tuic_node) = &nodes[1] else {
+            panic!("expected a TUIC node");
+        };
+        assert_eq!(
+            **tuic_node,
+            TuicNode {
+                remarks: Some(String::from("tuic")),
+                server: String::from("example.com"),
+                port: 10443,
+                uuid: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                password: String::from("PASSWORD_1"),
+                congestion_control: Some(TuicCongestionControl::Bbr),
+                udp_relay_mode: Some(TuicUdpRelayMode::Native),
+                tls: TlsOptions {
+                    sni: Some(String::from("tuic.example.com")),
+                    insecure: None,
+                    alpn: Some(vec![String::from("h3")]),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn parse_hysteria_proxies() {
+        let nodes = parse(
+            r#"
+proxies:
+  - name: "hysteria"
+    type: hysteria
+    server: example.com
+    port: 443
+    ports: 1000,2000-3000
+    auth-str: yourpassword
+    obfs: obfs_str
+    alpn:
+      - h3
+    protocol: udp
+    up: "30 Mbps"
+    down: 200
+    sni: hy.example.com
+    skip-cert-verify: false
+  - name: "hysteria2"
+    type: hysteria2
+    server: example.com
+    port: 443
+    ports: 20000-50000
+    password: yourpassword
+    up: 30
+    down: 200
+    obfs: salamander
+    obfs-password: yourpassword
+    sni: hy2.example.com
+    skip-cert-verify: true
+    alpn:
+      - h3
+  - name: "hysteria-without-bandwidth"
+    type: hysteria
+    server: example.com
+    port: 443
+    auth-str: yourpassword
+  - name: "hysteria2-with-unknown-obfs"
+    type: hysteria2
+    server: example.com
+    port: 443
+    obfs: unknown
+"#,
+        );
+
+        assert_eq!(nodes.len(), 2);
+
+        let Node::Hysteria(hysteria_node) = &nodes[0] else {
+            panic!("expected a Hysteria node");
+        };
+        assert_eq!(
+            **hysteria_node,
+            HysteriaNode {
+                remarks: Some(String::from("hysteria")),
+                server: String::from("example.com"),
+                port: ServerPort::Single(1000),
+                protocol: Some(HysteriaProtocol::Udp),
+                up: HysteriaSpeed::Text(String::from("30 Mbps")),
+                down: HysteriaSpeed::Mbps(200),
+                obfs: Some(String::from("obfs_str")),
+                auth: Some(String::from("yourpassword")),
+                tls: TlsOptions {
+                    sni: Some(String::from("hy.example.com")),
+                    insecure: Some(false),
+                    alpn: Some(vec![String::from("h3")]),
+                },
+            }
+        );
+
+        let Node::Hysteria2(hysteria2_node) = &nodes[1] else {
+            panic!("expected a Hysteria2 node");
+        };
+        assert_eq!(
+            **hysteria2_node,
+            Hysteria2Node {
+                remarks: Some(String::from("hysteria2")),
+                server: String::from("example.com"),
+                port: ServerPort::Range(20000, 50000),
+                auth: Some(String::from("yourpassword")),
+                obfs: Some(Hysteria2Obfuscation::Salamander {
+                    password: String::from("yourpassword"),
+                }),
+                up: Some(Hysteria2Speed::Mbps(30)),
+                down: Some(Hysteria2Speed::Mbps(200)),
+                tls: TlsOptions {
+                    sni: Some(String::from("hy2.example.com")),
+                    insecure: Some(true),
+                    alpn: Some(vec![String::from("h3")]),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn parse_wireguard_proxies() {
+        let nodes = parse(
+            r#"
+proxies:
+  - name: "wg"
+    type: wireguard
+    server: 162.159.192.1
+    port: 2480
+    ip: 172.16.0.2
+    ipv6: fd01:5ca1:ab1e:80fa:ab85:6eea:213f:f4a5
+    private-key: eCtXsJZ27+4PbhDkHnB923tkUn2Gj59wZw5wFA75MnU=
+    public-key: Cr8hWlKvtDt7nrvf+f0brNQQzabAqrjfBvas9pmowjo=
+    allowed-ips: ['0.0.0.0/0']
+    pre-shared-key: 31aIhAPwktDGpH4JDhA8GNvjFXEf/a6+UaQRyOAiyfM=
+    reserved: [209, 98, 59]
+    udp: true
+    mtu: 1408
+  - name: "wg-peers"
+    type: wireguard
+    ip: 172.16.0.2/32
+    private-key: eCtXsJZ27+4PbhDkHnB923tkUn2Gj59wZw5wFA75MnU=
+    udp: true
+    peers:
+      - server: engage.cloudflareclient.com
+        port: 2408
+        public-key: bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo=
+        reserved: U4An
+        allowed-ips: ['0.0.0.0/0']
+  - name: "wg-without-public-key"
+    type: wireguard
+    server: 162.159.192.1
+    port: 2480
+    private-key: eCtXsJZ27+4PbhDkHnB923tkUn2Gj59wZw5wFA75MnU=
+"#,
+        );
+
+        assert_eq!(nodes.len(), 2);
+
+        let Node::Wireguard(wireguard_node) = &nodes[0] else {
+            panic!("expected a WireGuard node");
+        };
+        assert_eq!(
+            *wireguard_node,
+            WireguardNode {
+                remarks: Some(String::from("wg")),
+                server: String::from("162.159.192.1"),
+                port: 2480,
+                ip: Some("172.16.0.2".parse().unwrap()),
+                ipv6: Some("fd01:5ca1:ab1e:80fa:ab85:6eea:213f:f4a5".parse().unwrap()),
+                private_key: String::from("eCtXsJZ27+4PbhDkHnB923tkUn2Gj59wZw5wFA75MnU="),
+                public_key: String::from("Cr8hWlKvtDt7nrvf+f0brNQQzabAqrjfBvas9pmowjo="),
+                pre_shared_key: Some(String::from("31aIhAPwktDGpH4JDhA8GNvjFXEf/a6+UaQRyOAiyfM=")),
+                reserved: Some([209, 98, 59]),
+                allowed_ips: Some(vec![String::from("0.0.0.0/0")]),
+            }
+        );
+
+        let Node::Wireguard(wireguard_node) = &nodes[1] else {
+            panic!("expected a WireGuard node");
+        };
+        assert_eq!(
+            *wireguard_node,
+            WireguardNode {
+                remarks: Some(String::from("wg-peers")),
+                server: String::from("engage.cloudflareclient.com"),
+                port: 2408,
+                ip: Some("172.16.0.2".parse().unwrap()),
+                ipv6: None,
+                private_key: String::from("eCtXsJZ27+4PbhDkHnB923tkUn2Gj59wZw5wFA75MnU="),
+                public_key: String::from("bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo="),
+                pre_shared_key: None,
+                reserved: Some([83, 128, 39]),
+                allowed_ips: Some(vec![String::from("0.0.0.0/0")]),
+            }
+        );
+    }
+}
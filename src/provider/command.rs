@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use std::process::Stdio;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use itertools::Itertools;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command as ProcessCommand;
+
+use crate::node::Node;
+use crate::utils::FetchOptions;
+
+use super::{
+    clash, sing_box, sip008, ssr, surge, uri_list, wireguard, CommonProviderOptions, Provider,
+    ProviderSource,
+};
+
+/// Run a command and use its stdout as the content of the provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Command {
+    /// Name of the provider.
+    pub name: Option<String>,
+
+    /// The program to run.
+    pub command: String,
+
+    /// Arguments passed to the program.
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Extra environment variables passed to the program.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+
+    /// The format of the command output.
+    pub format: CommandOutputFormat,
+
+    /// Common provider options.
+    #[serde(flatten)]
+    pub options: CommonProviderOptions,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommandOutputFormat {
+    Ssr,
+    Clash,
+    Wireguard,
+    Sip008,
+    UriList,
+    SingBox,
+    Surge,
+}
+
+#[async_trait]
+impl Provider for Command {
+    fn get_name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    fn get_source(&self) -> Option<&ProviderSource> {
+        None
+    }
+
+    fn get_source_mut(&mut self) -> Option<&mut ProviderSource> {
+        None
+    }
+
+    fn get_options(&self) -> &CommonProviderOptions {
+        &self.options
+    }
+
+    fn get_options_mut(&mut self) -> &mut CommonProviderOptions {
+        &mut self.options
+    }
+
+    fn get_display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            std::iter::once(&self.command)
+                .chain(self.args.iter())
+                .join(" ")
+        })
+    }
+
+    fn parse_nodes_from_content(&self, content: Bytes) -> Result<Vec<Node>> {
+        let name = self.get_display_name();
+        let options = &self.options;
+
+        match self.format {
+            CommandOutputFormat::Ssr => ssr::parse_nodes(content, &name, options),
+            CommandOutputFormat::Clash => clash::parse_nodes(content, &name),
+            CommandOutputFormat::Wireguard => {
+                wireguard::parse_nodes(content, self.name.as_deref().unwrap_or("WireGuard"))
+            }
+            CommandOutputFormat::Sip008 => sip008::parse_nodes(content, &name, options),
+            CommandOutputFormat::UriList => uri_list::parse_nodes(content, &name, options),
+            CommandOutputFormat::SingBox => sing_box::parse_nodes(content, &name, options),
+            CommandOutputFormat::Surge => surge::parse_nodes(content, &name, options),
+        }
+    }
+
+    async fn fetch_content(&self, fetch_options: &FetchOptions) -> Result<Bytes> {
+        debug!("running command `{}`", self.get_display_name());
+
+        // `output()` would capture stderr as well,
+        // spawn the child manually so its error messages go to our stderr.
+        // The child is killed if it is still running when the future is dropped on timeout.
+        let output = ProcessCommand::new(&self.command)
+            .args(&self.args)
+            .envs(&self.env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to run command `{}`", self.command))?
+            .wait_with_output();
+        let output = if let Some(timeout) = fetch_options.timeout {
+            tokio::time::timeout(timeout, output).await.map_err(|_| {
+                anyhow!(
+                    "command `{}` timed out after {}s",
+                    self.get_display_name(),
+                    timeout.as_secs_f64()
+                )
+            })?
+        } else {
+            output.await
+        }
+        .with_context(|| format!("failed to run command `{}`", self.command))?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "command `{}` exited with {}",
+                self.get_display_name(),
+                output.status
+            ));
+        }
+
+        Ok(Bytes::from(output.stdout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell(script: &str, format: CommandOutputFormat) -> Command {
+        Command {
+            name: None,
+            command: String::from("sh"),
+            args: vec![String::from("-c"), script.to_string()],
+            env: BTreeMap::from([(String::from("SERVER"), String::from("example.com"))]),
+            format,
+            options: CommonProviderOptions::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn parse_command_stdout() {
+        let command = shell(
+            r#"echo "trojan://password@$SERVER:443#trojan""#,
+            CommandOutputFormat::UriList,
+        );
+
+        let nodes = command
+            .parse_nodes_from_content(command.fetch_content_with_retries().await.unwrap())
+            .unwrap();
+        assert_eq!(nodes.len(), 1);
+        let Node::Trojan(trojan_node) = &nodes[0] else {
+            panic!("expected a Trojan node");
+        };
+        assert_eq!(trojan_node.remarks.as_deref(), Some("trojan"));
+        assert_eq!(trojan_node.server, "example.com");
+    }
+
+    #[tokio::test]
+    async fn fail_on_non_zero_exit_status() {
+        let command = shell("echo partial; exit 3", CommandOutputFormat::UriList);
+
+        let err = command.fetch_content_with_retries().await.unwrap_err();
+        assert!(err.to_string().contains("exited with exit status: 3"));
+    }
+
+    #[tokio::test]
+    async fn fail_on_timeout() {
+        let mut command = shell("sleep 10", CommandOutputFormat::UriList);
+        command.options.timeout = Some(1);
+
+        let err = command.fetch_content_with_retries().await.unwrap_err();
+        assert!(err.to_string().contains("timed out after 1s"));
+    }
+}
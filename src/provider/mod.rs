@@ -1,38 +1,138 @@
-use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
+use base64_simd::STANDARD as base64;
 use bytes::Bytes;
 use enum_dispatch::enum_dispatch;
-use http::Uri;
+use http::header::{HeaderName, HeaderValue, AUTHORIZATION, USER_AGENT};
+use http::{HeaderMap, Uri};
+use log::warn;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::time::sleep;
 
 use crate::node::Node;
-use crate::utils::{load_content_from_url, Path};
+use crate::utils::{load_content_from_url, parse_proxy, ContentCache, FetchOptions, Path};
 
 mod clash;
+mod command;
+mod sing_box;
+mod sip008;
 mod ssr;
+mod surge;
+mod uri_list;
+mod wireguard;
 
 pub use clash::Clash;
+pub use command::Command;
+pub use sing_box::SingBox;
+pub use sip008::Sip008;
 pub use ssr::Ssr;
+pub use surge::Surge;
+pub use uri_list::UriList;
+pub use wireguard::Wireguard;
+
+/// The delay between retries doubles from 1s, up to `2 ^ MAX_BACKOFF_EXPONENT` seconds.
+const MAX_BACKOFF_EXPONENT: u32 = 5;
 
 #[async_trait]
 #[enum_dispatch]
 pub trait Provider {
     fn get_name(&self) -> Option<&String>;
 
-    fn get_url(&self) -> &Uri;
+    /// Get where the content of the provider comes from.
+    /// Returns `None` if the content is not loaded from a source (e.g. a command).
+    fn get_source(&self) -> Option<&ProviderSource>;
+
+    fn get_source_mut(&mut self) -> Option<&mut ProviderSource>;
 
-    fn set_url(&mut self, url: Uri);
+    fn get_options(&self) -> &CommonProviderOptions;
+
+    fn get_options_mut(&mut self) -> &mut CommonProviderOptions;
 
     fn parse_nodes_from_content(&self, content: Bytes) -> Result<Vec<Node>>;
 
     fn get_display_name(&self) -> String {
         self.get_name()
             .map(|name| name.to_string())
-            .unwrap_or_else(|| self.get_url().to_string())
+            .or_else(|| self.get_source().map(|source| source.to_string()))
+            .unwrap_or_default()
+    }
+
+    /// Fetch the content, retrying with exponential backoff on failure.
+    async fn fetch_content_with_retries(&self) -> Result<Bytes> {
+        // Invalid options would fail every attempt, so check them before fetching.
+        let fetch_options = self.get_options().get_fetch_options().with_context(|| {
+            format!("invalid options in provider `{}`", self.get_display_name())
+        })?;
+        let retries = self.get_options().retries.unwrap_or_default();
+
+        let mut attempt = 0;
+        loop {
+            match self.fetch_content(&fetch_options).await {
+                Ok(content) => return Ok(content),
+
+                Err(e) if attempt < retries => {
+                    attempt += 1;
+                    let delay = Duration::from_secs(1 << (attempt - 1).min(MAX_BACKOFF_EXPONENT));
+                    warn!(
+                        "failed to fetch content of provider `{}`: {:#}, retrying in {}s ({}/{})",
+                        self.get_display_name(),
+                        e,
+                        delay.as_secs(),
+                        attempt,
+                        retries
+                    );
+                    sleep(delay).await;
+                }
+
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn fetch_content(&self, fetch_options: &FetchOptions) -> Result<Bytes> {
+        match self.get_source() {
+            Some(ProviderSource::Url(url)) => {
+                load_content_from_url(Path::Url(url.clone()), fetch_options).await
+            }
+            Some(ProviderSource::Path(path)) => {
+                load_content_from_url(Path::PathBuf(path.clone()), &FetchOptions::default()).await
+            }
+            Some(ProviderSource::Content(content)) => Ok(Bytes::from(content.clone())),
+            None => Err(anyhow!(
+                "provider `{}` does not have a source to load content from",
+                self.get_display_name()
+            )),
+        }
     }
+}
+
+/// Where the content of a provider comes from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderSource {
+    /// A remote resource fetched via network.
+    Url(#[serde(with = "http_serde::uri")] Uri),
 
-    async fn fetch_content(&self) -> Result<Bytes> {
-        load_content_from_url(Path::Url(self.get_url().clone())).await
+    /// A local file.
+    /// A relative path is resolved relative to the config file.
+    Path(PathBuf),
+
+    /// The content itself, e.g. a snapshot of a subscription.
+    Content(String),
+}
+impl Display for ProviderSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Url(url) => write!(f, "{url}"),
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Content(_) => write!(f, "<inline content>"),
+        }
     }
 }
 
@@ -42,6 +142,78 @@ pub trait Provider {
 pub enum Providers {
     Ssr(Ssr),
     Clash(Clash),
+    Wireguard(Wireguard),
+    Sip008(Sip008),
+    UriList(UriList),
+    SingBox(SingBox),
+    Surge(Surge),
+    Command(Command),
+}
+impl Providers {
+    fn get_type_name(&self) -> &'static str {
+        match self {
+            Self::Ssr(_) => "ssr",
+            Self::Clash(_) => "clash",
+            Self::Wireguard(_) => "wireguard",
+            Self::Sip008(_) => "sip008",
+            Self::UriList(_) => "uri-list",
+            Self::SingBox(_) => "sing-box",
+            Self::Surge(_) => "surge",
+            Self::Command(_) => "command",
+        }
+    }
+
+    /// Get the key of the provider in the content cache.
+    /// Only providers fetched from network are cached.
+    ///
+    /// The same URL may respond differently depending on the request headers
+    /// (e.g. the `User-Agent`), so the provider type and the options shaping the request
+    /// are part of the key as well.
+    pub fn get_cache_key(&self) -> Option<String> {
+        let options = self.get_options();
+        match self.get_source() {
+            Some(ProviderSource::Url(url)) if options.cache.unwrap_or(true) => Some(
+                json!({
+                    "type": self.get_type_name(),
+                    "url": url.to_string(),
+                    "headers": options.headers,
+                    "user_agent": options.user_agent,
+                    "basic_auth": options.basic_auth,
+                    "bearer_token": options.bearer_token,
+                })
+                .to_string(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Fetch the content of the provider,
+    /// and fall back to the cached content if fetching ultimately fails.
+    /// Returns the content and whether it is loaded from the cache.
+    pub async fn fetch_content_or_cached(
+        &self,
+        cache: Option<&ContentCache>,
+    ) -> Result<(Bytes, bool)> {
+        let err = match self.fetch_content_with_retries().await {
+            Ok(content) => return Ok((content, false)),
+            Err(err) => err,
+        };
+
+        let cached_content = match (cache, self.get_cache_key()) {
+            (Some(cache), Some(cache_key)) => cache.load(&cache_key).await,
+            _ => None,
+        };
+        let Some(cached_content) = cached_content else {
+            return Err(err);
+        };
+        warn!(
+            "failed to fetch content of provider `{}`, using the cached content instead: {:#}",
+            self.get_display_name(),
+            err
+        );
+
+        Ok((cached_content, true))
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -57,4 +229,341 @@ pub struct CommonProviderOptions {
 
     /// Override the `uot` field in all ShadowsocksR nodes.
     pub ssr_uot: Option<bool>,
+
+    /// Extra HTTP headers sent when fetching the provider.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// The `User-Agent` header sent when fetching the provider.
+    /// Some providers return different formats depending on it.
+    pub user_agent: Option<String>,
+
+    /// Credentials for HTTP basic authentication.
+    pub basic_auth: Option<BasicAuth>,
+
+    /// Token for HTTP bearer authentication.
+    pub bearer_token: Option<String>,
+
+    /// Timeout in seconds when fetching the provider.
+    pub timeout: Option<u64>,
+
+    /// The HTTP or SOCKS5 proxy used when fetching the provider,
+    /// e.g. `http://127.0.0.1:8080` or `socks5://127.0.0.1:1080`.
+    pub fetch_proxy: Option<String>,
+
+    /// How many times to retry when fetching the provider fails.
+    /// Defaults to `0`.
+    pub retries: Option<u32>,
+
+    /// Whether to fall back to the last successfully fetched content
+    /// when fetching the provider ultimately fails.
+    /// Defaults to `true`, so the content of every provider fetched from a URL
+    /// is written to the cache directory unless this is set to `false`
+    /// or the cache is disabled with `--no-cache`.
+    pub cache: Option<bool>,
+}
+impl CommonProviderOptions {
+    /// Get the options used when fetching the provider from network.
+    pub fn get_fetch_options(&self) -> Result<FetchOptions> {
+        let mut headers = HeaderMap::new();
+
+        for (name, value) in &self.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes())
+                    .with_context(|| format!("invalid header name `{name}`"))?,
+                HeaderValue::from_str(value)
+                    .with_context(|| format!("invalid value of header `{name}`"))?,
+            );
+        }
+
+        if let Some(user_agent) = &self.user_agent {
+            headers.insert(
+                USER_AGENT,
+                HeaderValue::from_str(user_agent).context("invalid `user_agent`")?,
+            );
+        }
+
+        let authorization = match (&self.basic_auth, &self.bearer_token) {
+            (Some(_), Some(_)) => {
+                return Err(anyhow!(
+                    "`basic_auth` and `bearer_token` can not be set at the same time"
+                ))
+            }
+            (Some(basic_auth), None) => Some(format!(
+                "Basic {}",
+                base64.encode_to_string(format!(
+                    "{}:{}",
+                    basic_auth.username,
+                    basic_auth.password.as_deref().unwrap_or_default()
+                ))
+            )),
+            (None, Some(bearer_token)) => Some(format!("Bearer {bearer_token}")),
+            (None, None) => None,
+        };
+        if let Some(authorization) = authorization {
+            let mut value = HeaderValue::from_str(&authorization)
+                .context("invalid `basic_auth` or `bearer_token`")?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+
+        let proxy = self
+            .fetch_proxy
+            .as_deref()
+            .map(parse_proxy)
+            .transpose()
+            .context("invalid `fetch_proxy`")?;
+
+        Ok(FetchOptions {
+            headers,
+            timeout: self.timeout.map(Duration::from_secs),
+            proxy,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BasicAuth {
+    pub username: String,
+    pub password: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    fn basic_auth(username: &str, password: Option<&str>) -> Option<BasicAuth> {
+        Some(BasicAuth {
+            username: username.to_string(),
+            password: password.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn encode_basic_auth() {
+        let options = CommonProviderOptions {
+            basic_auth: basic_auth("user", Some("pass")),
+            ..Default::default()
+        };
+        let fetch_options = options.get_fetch_options().unwrap();
+        let authorization = fetch_options.headers.get(AUTHORIZATION).unwrap();
+        assert_eq!(authorization, "Basic dXNlcjpwYXNz");
+        assert!(authorization.is_sensitive());
+
+        let options = CommonProviderOptions {
+            basic_auth: basic_auth("user", None),
+            ..Default::default()
+        };
+        let fetch_options = options.get_fetch_options().unwrap();
+        assert_eq!(
+            fetch_options.headers.get(AUTHORIZATION).unwrap(),
+            "Basic dXNlcjo="
+        );
+    }
+
+    #[test]
+    fn encode_bearer_token() {
+        let options = CommonProviderOptions {
+            bearer_token: Some("token".to_string()),
+            ..Default::default()
+        };
+        let fetch_options = options.get_fetch_options().unwrap();
+        assert_eq!(
+            fetch_options.headers.get(AUTHORIZATION).unwrap(),
+            "Bearer token"
+        );
+    }
+
+    #[test]
+    fn reject_both_basic_auth_and_bearer_token() {
+        let options = CommonProviderOptions {
+            basic_auth: basic_auth("user", Some("pass")),
+            bearer_token: Some("token".to_string()),
+            ..Default::default()
+        };
+        assert!(options.get_fetch_options().is_err());
+    }
+
+    #[test]
+    fn reject_invalid_headers() {
+        let options = CommonProviderOptions {
+            headers: BTreeMap::from([("Invalid Name".to_string(), "value".to_string())]),
+            ..Default::default()
+        };
+        assert!(options.get_fetch_options().is_err());
+
+        let options = CommonProviderOptions {
+            headers: BTreeMap::from([("X-Name".to_string(), "line\nbreak".to_string())]),
+            ..Default::default()
+        };
+        assert!(options.get_fetch_options().is_err());
+
+        let options = CommonProviderOptions {
+            user_agent: Some("line\nbreak".to_string()),
+            ..Default::default()
+        };
+        assert!(options.get_fetch_options().is_err());
+    }
+
+    #[test]
+    fn override_user_agent_in_headers() {
+        let options = CommonProviderOptions {
+            headers: BTreeMap::from([
+                ("User-Agent".to_string(), "from-headers".to_string()),
+                ("X-Name".to_string(), "value".to_string()),
+            ]),
+            user_agent: Some("from-user-agent".to_string()),
+            ..Default::default()
+        };
+        let fetch_options = options.get_fetch_options().unwrap();
+        assert_eq!(fetch_options.headers.len(), 2);
+        assert_eq!(
+            fetch_options.headers.get(USER_AGENT).unwrap(),
+            "from-user-agent"
+        );
+        assert_eq!(fetch_options.headers.get("x-name").unwrap(), "value");
+    }
+
+    /// A provider whose first `failures` fetches fail.
+    struct FlakyProvider {
+        failures: u32,
+        attempts: AtomicU32,
+        options: CommonProviderOptions,
+    }
+    impl FlakyProvider {
+        fn new(failures: u32, options: CommonProviderOptions) -> Self {
+            Self {
+                failures,
+                attempts: AtomicU32::new(0),
+                options,
+            }
+        }
+    }
+    #[async_trait]
+    impl Provider for FlakyProvider {
+        fn get_name(&self) -> Option<&String> {
+            None
+        }
+
+        fn get_source(&self) -> Option<&ProviderSource> {
+            None
+        }
+
+        fn get_source_mut(&mut self) -> Option<&mut ProviderSource> {
+            None
+        }
+
+        fn get_options(&self) -> &CommonProviderOptions {
+            &self.options
+        }
+
+        fn get_options_mut(&mut self) -> &mut CommonProviderOptions {
+            &mut self.options
+        }
+
+        fn parse_nodes_from_content(&self, _content: Bytes) -> Result<Vec<Node>> {
+            Ok(Vec::new())
+        }
+
+        async fn fetch_content(&self, _fetch_options: &FetchOptions) -> Result<Bytes> {
+            let attempt = self.attempts.fetch_add(1, Ordering::SeqCst);
+            if attempt < self.failures {
+                Err(anyhow!("attempt {} failed", attempt + 1))
+            } else {
+                Ok(Bytes::from("content"))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_until_success() {
+        let provider = FlakyProvider::new(
+            1,
+            CommonProviderOptions {
+                retries: Some(1),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            provider.fetch_content_with_retries().await.unwrap(),
+            "content"
+        );
+        assert_eq!(provider.attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn fail_without_retries() {
+        let provider = FlakyProvider::new(1, CommonProviderOptions::default());
+
+        assert!(provider.fetch_content_with_retries().await.is_err());
+        assert_eq!(provider.attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn never_retry_invalid_options() {
+        let provider = FlakyProvider::new(
+            0,
+            CommonProviderOptions {
+                headers: BTreeMap::from([("Invalid Name".to_string(), "value".to_string())]),
+                retries: Some(3),
+                ..Default::default()
+            },
+        );
+
+        assert!(provider.fetch_content_with_retries().await.is_err());
+        assert_eq!(provider.attempts.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn fall_back_to_cached_content() {
+        // Nothing listens on the port once the listener is dropped.
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let get_provider = |cache| {
+            Providers::UriList(UriList {
+                name: None,
+                source: ProviderSource::Url(format!("http://{addr}/sub").parse().unwrap()),
+                options: CommonProviderOptions {
+                    cache,
+                    // Keep proxies from the environment from answering the request.
+                    fetch_proxy: Some(format!("http://{addr}")),
+                    ..Default::default()
+                },
+            })
+        };
+
+        let cache_directory =
+            std::env::temp_dir().join(format!("ringer-test-{}-fallback", std::process::id()));
+        let cache = ContentCache::new(cache_directory.clone());
+        let provider = get_provider(None);
+        cache
+            .store(
+                &provider.get_cache_key().unwrap(),
+                &Bytes::from("cached content"),
+            )
+            .await
+            .unwrap();
+
+        let (content, is_from_cache) = provider
+            .fetch_content_or_cached(Some(&cache))
+            .await
+            .unwrap();
+        assert_eq!(content, "cached content");
+        assert!(is_from_cache);
+
+        assert!(provider.fetch_content_or_cached(None).await.is_err());
+        assert!(get_provider(Some(false))
+            .fetch_content_or_cached(Some(&cache))
+            .await
+            .is_err());
+
+        std::fs::remove_dir_all(cache_directory).unwrap();
+    }
 }
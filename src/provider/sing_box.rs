@@ -0,0 +1,1028 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{serde_as, OneOrMany};
+use uuid::Uuid;
+
+use crate::node::common::{ServerPort, Speed, TlsOptions, Transport};
+use crate::node::hysteria2::Obfuscation as Hysteria2Obfuscation;
+use crate::node::ss::{Method as SsMethod, Plugin as SsPlugin};
+use crate::node::tuic::{
+    CongestionControl as TuicCongestionControl, UdpRelayMode as TuicUdpRelayMode,
+};
+use crate::node::vless::RealityOptions;
+use crate::node::vmess::Security as VmessSecurity;
+use crate::node::wireguard::parse_addresses;
+use crate::node::{
+    HttpNode, Hysteria2Node, HysteriaNode, Node, Socks5Node, SsNode, SsrNode, TrojanNode, TuicNode,
+    VlessNode, VmessNode, WireguardNode,
+};
+use crate::template::adaptors::sing_box::SingBoxHysteria2Obfuscation;
+
+use super::{CommonProviderOptions, Provider, ProviderSource};
+
+/// Outbounds which are not proxies, so they are skipped without warnings.
+const NON_PROXY_OUTBOUND_TYPES: [&str; 5] = ["direct", "block", "dns", "selector", "urltest"];
+
+/// sing-box configuration.
+/// Only the `outbounds` are used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SingBox {
+    /// Name of the sing-box configuration.
+    pub name: Option<String>,
+
+    /// Where to load the sing-box configuration from,
+    /// which could be a `url`, a local `path` or the inline `content`.
+    #[serde(flatten)]
+    pub source: ProviderSource,
+
+    /// Common provider options.
+    #[serde(flatten)]
+    pub options: CommonProviderOptions,
+}
+
+#[async_trait]
+impl Provider for SingBox {
+    fn get_name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    fn get_source(&self) -> Option<&ProviderSource> {
+        Some(&self.source)
+    }
+
+    fn get_source_mut(&mut self) -> Option<&mut ProviderSource> {
+        Some(&mut self.source)
+    }
+
+    fn get_options(&self) -> &CommonProviderOptions {
+        &self.options
+    }
+
+    fn get_options_mut(&mut self) -> &mut CommonProviderOptions {
+        &mut self.options
+    }
+
+    fn parse_nodes_from_content(&self, content: Bytes) -> Result<Vec<Node>> {
+        parse_nodes(content, &self.get_display_name(), &self.options)
+    }
+}
+
+/// Parse the content of a sing-box configuration.
+/// `provider_name` is only used in log messages.
+pub(super) fn parse_nodes(
+    content: Bytes,
+    provider_name: &str,
+    options: &CommonProviderOptions,
+) -> Result<Vec<Node>> {
+    let sing_box_config: SingBoxConfiguration = serde_json::from_slice(&content)
+        .context("failed to parse the provider content as sing-box configuration json")?;
+
+    Ok(sing_box_config
+        .outbounds
+        .into_iter()
+        .filter_map(|outbound| {
+            let node = match outbound {
+                ImplementedOutboundOrUnknownOutbound::Implemented(outbound) => {
+                    sing_box_outbound_to_node(*outbound, options)
+                }
+                ImplementedOutboundOrUnknownOutbound::Unknown(outbound) => {
+                    let outbound_type = outbound
+                        .get("type")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    if !NON_PROXY_OUTBOUND_TYPES.contains(&outbound_type) {
+                        warn!(
+                            "unsupported or invalid outbound `{}` of type `{}` in provider `{}`, skip it",
+                            outbound.get("tag").and_then(Value::as_str).unwrap_or_default(),
+                            outbound_type,
+                            provider_name
+                        );
+                    }
+                    return None;
+                }
+            };
+
+            match node {
+                Ok(node) => Some(node),
+                Err(err) => {
+                    warn!(
+                        "failed to parse outbound in provider `{}`: {:#}, skip it",
+                        provider_name,
+                        err
+                    );
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+fn sing_box_outbound_to_node(
+    outbound: SingBoxOutbound,
+    options: &CommonProviderOptions,
+) -> Result<Node> {
+    match outbound {
+        SingBoxOutbound::Shadowsocks {
+            tag,
+            server,
+            server_port,
+            method,
+            password,
+            plugin,
+            plugin_opts,
+            network,
+            udp_over_tcp,
+        } => {
+            let name = tag.as_deref().unwrap_or(&server);
+            let method = SsMethod::from_alias(&method)
+                .ok_or_else(|| anyhow!("Unknown method `{}` in outbound `{}`", method, name))?;
+            let plugin = plugin
+                .map(|plugin| {
+                    SsPlugin::from_name_and_opts_str(
+                        plugin,
+                        plugin_opts.as_deref().unwrap_or_default(),
+                    )
+                    .with_context(|| format!("failed to parse SS plugin of outbound `{name}`"))
+                })
+                .transpose()?;
+
+            Ok(Node::Ss(Box::new(SsNode {
+                id: None,
+                remarks: tag,
+                server,
+                server_port,
+                password,
+                method,
+                udp: options.ss_udp.or(match network.as_deref() {
+                    Some("tcp") => Some(false),
+                    _ => None,
+                }),
+                udp_over_tcp: options.ss_udp_over_tcp.or(match udp_over_tcp {
+                    Some(SingBoxUdpOverTcp::Enabled(enabled)) => Some(enabled),
+                    Some(SingBoxUdpOverTcp::Options { enabled }) => Some(enabled),
+                    None => None,
+                }),
+                plugin,
+            })))
+        }
+
+        SingBoxOutbound::Shadowsocksr {
+            tag,
+            server,
+            server_port,
+            method,
+            password,
+            obfs,
+            obfs_param,
+            protocol,
+            protocol_param,
+        } => Ok(Node::Ssr(Box::new(SsrNode {
+            remarks: tag,
+            server,
+            server_port,
+            password,
+            method,
+            protocol,
+            protocol_param,
+            obfs,
+            obfs_param,
+            udpport: options.ssr_udpport,
+            uot: options.ssr_uot,
+        }))),
+
+        SingBoxOutbound::Hysteria {
+            tag,
+            server,
+            server_port,
+            server_ports,
+            up,
+            up_mbps,
+            down,
+            down_mbps,
+            obfs,
+            auth_str,
+            tls,
+        } => {
+            let name = tag.as_deref().unwrap_or(&server);
+            let port = get_server_port(name, server_port, server_ports)?;
+            let up = up
+                .map(Speed::Text)
+                .or(up_mbps.map(Speed::Mbps))
+                .ok_or_else(|| anyhow!("outbound `{}` does not contain `up`", name))?;
+            let down = down
+                .map(Speed::Text)
+                .or(down_mbps.map(Speed::Mbps))
+                .ok_or_else(|| anyhow!("outbound `{}` does not contain `down`", name))?;
+
+            Ok(Node::Hysteria(Box::new(HysteriaNode {
+                remarks: tag,
+                server,
+                port,
+                protocol: None,
+                up,
+                down,
+                obfs,
+                auth: auth_str,
+                tls: tls.map(Into::into).unwrap_or_default(),
+            })))
+        }
+
+        SingBoxOutbound::Hysteria2 {
+            tag,
+            server,
+            server_port,
+            server_ports,
+            up_mbps,
+            down_mbps,
+            obfs,
+            password,
+            tls,
+        } => Ok(Node::Hysteria2(Box::new(Hysteria2Node {
+            port: get_server_port(tag.as_deref().unwrap_or(&server), server_port, server_ports)?,
+            remarks: tag,
+            server,
+            auth: password,
+            obfs: obfs.map(|obfs| match obfs {
+                SingBoxHysteria2Obfuscation::Salamander { password } => {
+                    Hysteria2Obfuscation::Salamander { password }
+                }
+            }),
+            up: up_mbps.map(Speed::Mbps),
+            down: down_mbps.map(Speed::Mbps),
+            tls: tls.map(Into::into).unwrap_or_default(),
+        }))),
+
+        SingBoxOutbound::Wireguard {
+            tag,
+            server,
+            server_port,
+            local_address,
+            private_key,
+            peer_public_key,
+            pre_shared_key,
+            reserved,
+        } => {
+            let (ip, ipv6) = parse_addresses(&local_address.join(",")).with_context(|| {
+                format!(
+                    "invalid `local_address` of outbound `{}`",
+                    tag.as_deref().unwrap_or(&server)
+                )
+            })?;
+
+            Ok(Node::Wireguard(WireguardNode {
+                remarks: tag,
+                server,
+                port: server_port,
+                ip,
+                ipv6,
+                private_key,
+                public_key: peer_public_key,
+                pre_shared_key,
+                reserved,
+                allowed_ips: None,
+            }))
+        }
+
+        SingBoxOutbound::Vmess {
+            tag,
+            server,
+            server_port,
+            uuid,
+            security,
+            alter_id,
+            tls,
+            transport,
+        } => {
+            let name = tag.as_deref().unwrap_or(&server);
+            let uuid = parse_uuid(name, &uuid)?;
+            let security = security
+                .map(|security| {
+                    VmessSecurity::from_alias(&security).ok_or_else(|| {
+                        anyhow!("Unknown security `{}` in outbound `{}`", security, name)
+                    })
+                })
+                .transpose()?;
+
+            Ok(Node::Vmess(Box::new(VmessNode {
+                remarks: tag,
+                server,
+                port: server_port,
+                uuid,
+                alter_id,
+                security,
+                transport: transport.map(Into::into),
+                tls: tls.filter(|tls| tls.enabled).map(Into::into),
+            })))
+        }
+
+        SingBoxOutbound::Vless {
+            tag,
+            server,
+            server_port,
+            uuid,
+            flow,
+            tls,
+            transport,
+        } => {
+            let uuid = parse_uuid(tag.as_deref().unwrap_or(&server), &uuid)?;
+            let tls = tls.filter(|tls| tls.enabled);
+            let fingerprint = tls
+                .as_ref()
+                .and_then(|tls| tls.utls.as_ref())
+                .filter(|utls| utls.enabled)
+                .and_then(|utls| utls.fingerprint.clone());
+            let reality = tls
+                .as_ref()
+                .and_then(|tls| tls.reality.as_ref())
+                .filter(|reality| reality.enabled)
+                .map(|reality| RealityOptions {
+                    public_key: reality.public_key.clone(),
+                    short_id: reality.short_id.clone(),
+                });
+
+            Ok(Node::Vless(Box::new(VlessNode {
+                remarks: tag,
+                server,
+                port: server_port,
+                uuid,
+                flow,
+                transport: transport.map(Into::into),
+                tls: tls.map(Into::into),
+                fingerprint,
+                reality,
+            })))
+        }
+
+        SingBoxOutbound::Trojan {
+            tag,
+            server,
+            server_port,
+            password,
+            tls,
+            transport,
+        } => Ok(Node::Trojan(Box::new(TrojanNode {
+            remarks: tag,
+            server,
+            port: server_port,
+            password,
+            transport: transport.map(Into::into),
+            tls: tls.map(Into::into).unwrap_or_default(),
+        }))),
+
+        SingBoxOutbound::Tuic {
+            tag,
+            server,
+            server_port,
+            uuid,
+            password,
+            congestion_control,
+            udp_relay_mode,
+            tls,
+        } => {
+            let name = tag.as_deref().unwrap_or(&server);
+            let uuid = parse_uuid(name, &uuid)?;
+            let congestion_control = congestion_control
+                .map(|congestion_control| {
+                    TuicCongestionControl::from_alias(&congestion_control).ok_or_else(|| {
+                        anyhow!(
+                            "Unknown congestion control `{}` in outbound `{}`",
+                            congestion_control,
+                            name
+                        )
+                    })
+                })
+                .transpose()?;
+            let udp_relay_mode = udp_relay_mode
+                .map(|udp_relay_mode| {
+                    TuicUdpRelayMode::from_alias(&udp_relay_mode).ok_or_else(|| {
+                        anyhow!(
+                            "Unknown UDP relay mode `{}` in outbound `{}`",
+                            udp_relay_mode,
+                            name
+                        )
+                    })
+                })
+                .transpose()?;
+
+            Ok(Node::Tuic(Box::new(TuicNode {
+                remarks: tag,
+                server,
+                port: server_port,
+                uuid,
+                password,
+                congestion_control,
+                udp_relay_mode,
+                tls: tls.map(Into::into).unwrap_or_default(),
+            })))
+        }
+
+        SingBoxOutbound::Socks {
+            tag,
+            server,
+            server_port,
+            version,
+            username,
+            password,
+        } => {
+            if let Some(version) = version.filter(|version| version != "5") {
+                return Err(anyhow!(
+                    "SOCKS version `{}` of outbound `{}` is not supported",
+                    version,
+                    tag.as_deref().unwrap_or(&server)
+                ));
+            }
+
+            Ok(Node::Socks5(Box::new(Socks5Node {
+                remarks: tag,
+                server,
+                port: server_port,
+                username,
+                password,
+                tls: None,
+            })))
+        }
+
+        SingBoxOutbound::Http {
+            tag,
+            server,
+            server_port,
+            username,
+            password,
+            tls,
+        } => Ok(Node::Http(Box::new(HttpNode {
+            remarks: tag,
+            server,
+            port: server_port,
+            username,
+            password,
+            tls: tls.filter(|tls| tls.enabled).map(Into::into),
+        }))),
+    }
+}
+
+/// Get the port from `server_port` or the port hopping `server_ports`,
+/// e.g. `["20000:30000"]`, only the first port range is used.
+fn get_server_port(
+    name: &str,
+    server_port: Option<u16>,
+    server_ports: Option<Vec<String>>,
+) -> Result<ServerPort> {
+    match server_ports.as_deref().and_then(<[String]>::first) {
+        Some(ports) => ServerPort::from_port_str(&ports.replace(':', "-"))
+            .with_context(|| format!("invalid `server_ports` of outbound `{name}`")),
+        None => server_port
+            .map(ServerPort::Single)
+            .ok_or_else(|| anyhow!("outbound `{}` does not contain `server_port`", name)),
+    }
+}
+
+fn parse_uuid(name: &str, uuid: &str) -> Result<Uuid> {
+    Uuid::parse_str(uuid).with_context(|| format!("invalid uuid `{uuid}` of outbound `{name}`"))
+}
+
+/// Outbounds which are not proxies (e.g. `selector`) fall into `Unknown`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ImplementedOutboundOrUnknownOutbound {
+    Implemented(Box<SingBoxOutbound>),
+    Unknown(Value),
+}
+
+/// The outbounds which could be converted to nodes.
+/// Reference: https://sing-box.sagernet.org/configuration/outbound/
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum SingBoxOutbound {
+    Shadowsocks {
+        tag: Option<String>,
+        server: String,
+        server_port: u16,
+        method: String,
+        password: String,
+        plugin: Option<String>,
+        plugin_opts: Option<String>,
+        network: Option<String>,
+        udp_over_tcp: Option<SingBoxUdpOverTcp>,
+    },
+
+    Shadowsocksr {
+        tag: Option<String>,
+        server: String,
+        server_port: u16,
+        method: String,
+        password: String,
+        obfs: String,
+        obfs_param: Option<String>,
+        protocol: String,
+        protocol_param: Option<String>,
+    },
+
+    Hysteria {
+        tag: Option<String>,
+        server: String,
+        server_port: Option<u16>,
+        #[serde_as(as = "Option<OneOrMany<_>>")]
+        #[serde(default)]
+        server_ports: Option<Vec<String>>,
+        up: Option<String>,
+        up_mbps: Option<u32>,
+        down: Option<String>,
+        down_mbps: Option<u32>,
+        obfs: Option<String>,
+        auth_str: Option<String>,
+        tls: Option<SingBoxTlsOptions>,
+    },
+
+    Hysteria2 {
+        tag: Option<String>,
+        server: String,
+        server_port: Option<u16>,
+        #[serde_as(as = "Option<OneOrMany<_>>")]
+        #[serde(default)]
+        server_ports: Option<Vec<String>>,
+        up_mbps: Option<u32>,
+        down_mbps: Option<u32>,
+        obfs: Option<SingBoxHysteria2Obfuscation>,
+        password: Option<String>,
+        tls: Option<SingBoxTlsOptions>,
+    },
+
+    Wireguard {
+        tag: Option<String>,
+        server: String,
+        server_port: u16,
+        #[serde_as(as = "OneOrMany<_>")]
+        local_address: Vec<String>,
+        private_key: String,
+        peer_public_key: String,
+        pre_shared_key: Option<String>,
+        reserved: Option<[u8; 3]>,
+    },
+
+    Vmess {
+        tag: Option<String>,
+        server: String,
+        server_port: u16,
+        uuid: String,
+        security: Option<String>,
+        alter_id: Option<u16>,
+        tls: Option<SingBoxTlsOptions>,
+        transport: Option<SingBoxTransport>,
+    },
+
+    Vless {
+        tag: Option<String>,
+        server: String,
+        server_port: u16,
+        uuid: String,
+        flow: Option<String>,
+        tls: Option<SingBoxTlsOptions>,
+        transport: Option<SingBoxTransport>,
+    },
+
+    Trojan {
+        tag: Option<String>,
+        server: String,
+        server_port: u16,
+        password: String,
+        tls: Option<SingBoxTlsOptions>,
+        transport: Option<SingBoxTransport>,
+    },
+
+    Tuic {
+        tag: Option<String>,
+        server: String,
+        server_port: u16,
+        uuid: String,
+        password: String,
+        congestion_control: Option<String>,
+        udp_relay_mode: Option<String>,
+        tls: Option<SingBoxTlsOptions>,
+    },
+
+    Socks {
+        tag: Option<String>,
+        server: String,
+        server_port: u16,
+        version: Option<String>,
+        username: Option<String>,
+        password: Option<String>,
+    },
+
+    Http {
+        tag: Option<String>,
+        server: String,
+        server_port: u16,
+        username: Option<String>,
+        password: Option<String>,
+        tls: Option<SingBoxTlsOptions>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SingBoxUdpOverTcp {
+    Enabled(bool),
+    Options { enabled: bool },
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+struct SingBoxTlsOptions {
+    #[serde(default)]
+    enabled: bool,
+    server_name: Option<String>,
+    insecure: Option<bool>,
+    #[serde_as(as = "Option<OneOrMany<_>>")]
+    #[serde(default)]
+    alpn: Option<Vec<String>>,
+    utls: Option<SingBoxUtlsOptions>,
+    reality: Option<SingBoxRealityOptions>,
+}
+impl From<SingBoxTlsOptions> for TlsOptions {
+    fn from(value: SingBoxTlsOptions) -> Self {
+        Self {
+            sni: value.server_name,
+            insecure: value.insecure,
+            alpn: value.alpn,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SingBoxUtlsOptions {
+    #[serde(default)]
+    enabled: bool,
+    fingerprint: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SingBoxRealityOptions {
+    #[serde(default)]
+    enabled: bool,
+    public_key: String,
+    short_id: Option<String>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum SingBoxTransport {
+    Ws {
+        path: Option<String>,
+        #[serde_as(as = "Option<BTreeMap<_, OneOrMany<_>>>")]
+        #[serde(default)]
+        headers: Option<BTreeMap<String, Vec<String>>>,
+    },
+    Http {
+        #[serde_as(as = "Option<OneOrMany<_>>")]
+        #[serde(default)]
+        host: Option<Vec<String>>,
+        path: Option<String>,
+    },
+    Grpc {
+        service_name: Option<String>,
+    },
+}
+impl From<SingBoxTransport> for Transport {
+    fn from(value: SingBoxTransport) -> Self {
+        match value {
+            SingBoxTransport::Ws { path, headers } => Self::Ws {
+                path,
+                host: headers
+                    .and_then(|mut headers| headers.remove("Host"))
+                    .and_then(|host| host.into_iter().next()),
+            },
+            SingBoxTransport::Http { host, path } => Self::H2 { path, host },
+            SingBoxTransport::Grpc { service_name } => Self::Grpc { service_name },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SingBoxConfiguration {
+    outbounds: Vec<ImplementedOutboundOrUnknownOutbound>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::GetNodeName;
+
+    fn parse(content: &str) -> Result<Vec<Node>> {
+        let provider = SingBox {
+            name: None,
+            source: ProviderSource::Content(content.to_string()),
+            options: CommonProviderOptions::default(),
+        };
+
+        provider.parse_nodes_from_content(Bytes::from(content.to_string()))
+    }
+
+    #[test]
+    fn parse_outbounds() {
+        let nodes = parse(
+            r#"{
+                "outbounds": [
+                    { "type": "selector", "tag": "proxy", "outbounds": ["ss", "hy2"] },
+                    {
+                        "type": "shadowsocks",
+                        "tag": "ss",
+                        "server": "example.com",
+                        "server_port": 8388,
+                        "method": "aes-256-gcm",
+                        "password": "password",
+                        "plugin": "obfs-local",
+                        "plugin_opts": "obfs=http;obfs-host=www.bing.com",
+                        "network": "tcp",
+                        "udp_over_tcp": { "enabled": true }
+                    },
+                    {
+                        "type": "hysteria2",
+                        "tag": "hy2",
+                        "server": "example.com",
+                        "server_ports": "20000:30000",
+                        "password": "password",
+                        "tls": { "enabled": true, "server_name": "sni.example.com", "alpn": "h3" }
+                    },
+                    {
+                        "type": "wireguard",
+                        "server": "example.com",
+                        "server_port": 51820,
+                        "local_address": ["10.0.0.2/32", "fd00::2/128"],
+                        "private_key": "private_key",
+                        "peer_public_key": "public_key"
+                    },
+                    {
+                        "type": "vmess",
+                        "server": "example.com",
+                        "server_port": 443,
+                        "uuid": "bf000d23-0752-40b4-affe-68f7707a9661"
+                    },
+                    { "type": "direct", "tag": "direct" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(nodes.len(), 4);
+
+        let Node::Ss(ss_node) = &nodes[0] else {
+            panic!("expected a Shadowsocks node");
+        };
+        assert_eq!(ss_node.remarks.as_deref(), Some("ss"));
+        assert_eq!(ss_node.method, SsMethod::AeadAes256Gcm);
+        assert_eq!(ss_node.udp, Some(false));
+        assert_eq!(ss_node.udp_over_tcp, Some(true));
+        assert_eq!(
+            ss_node.plugin.as_ref().and_then(SsPlugin::get_opts_string),
+            Some("obfs=http;obfs-host=www.bing.com".to_string())
+        );
+
+        let Node::Hysteria2(hysteria2_node) = &nodes[1] else {
+            panic!("expected a Hysteria2 node");
+        };
+        assert_eq!(hysteria2_node.port, ServerPort::Range(20000, 30000));
+        assert_eq!(hysteria2_node.auth.as_deref(), Some("password"));
+        assert_eq!(hysteria2_node.tls.sni.as_deref(), Some("sni.example.com"));
+        assert_eq!(hysteria2_node.tls.alpn, Some(vec!["h3".to_string()]));
+
+        let Node::Wireguard(wireguard_node) = &nodes[2] else {
+            panic!("expected a WireGuard node");
+        };
+        assert_eq!(wireguard_node.ip, Some("10.0.0.2".parse().unwrap()));
+        assert_eq!(wireguard_node.ipv6, Some("fd00::2".parse().unwrap()));
+
+        let Node::Vmess(vmess_node) = &nodes[3] else {
+            panic!("expected a VMess node");
+        };
+        assert_eq!(vmess_node.remarks, None);
+        assert_eq!(vmess_node.port, 443);
+    }
+
+    #[test]
+    fn parse_v2ray_outbounds() {
+        let nodes = parse(
+            r#"{
+                "outbounds": [
+                    {
+                        "type": "vmess",
+                        "tag": "vmess",
+                        "server": "example.com",
+                        "server_port": 443,
+                        "uuid": "bf000d23-0752-40b4-affe-68f7707a9661",
+                        "security": "auto",
+                        "alter_id": 0,
+                        "tls": { "enabled": true, "server_name": "example.com" },
+                        "transport": {
+                            "type": "ws",
+                            "path": "/ws",
+                            "headers": { "Host": "cdn.example.com" }
+                        }
+                    },
+                    {
+                        "type": "vless",
+                        "tag": "vless",
+                        "server": "example.com",
+                        "server_port": 443,
+                        "uuid": "bf000d23-0752-40b4-affe-68f7707a9661",
+                        "flow": "xtls-rprx-vision",
+                        "tls": {
+                            "enabled": true,
+                            "server_name": "www.microsoft.com",
+                            "utls": { "enabled": true, "fingerprint": "chrome" },
+                            "reality": { "enabled": true, "public_key": "public_key", "short_id": "0123" }
+                        }
+                    },
+                    {
+                        "type": "trojan",
+                        "tag": "trojan",
+                        "server": "example.com",
+                        "server_port": 443,
+                        "password": "password",
+                        "tls": { "enabled": true, "insecure": true },
+                        "transport": { "type": "grpc", "service_name": "grpc" }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(nodes.len(), 3);
+
+        let Node::Vmess(vmess_node) = &nodes[0] else {
+            panic!("expected a VMess node");
+        };
+        assert_eq!(vmess_node.security, Some(VmessSecurity::Auto));
+        assert_eq!(
+            vmess_node.transport,
+            Some(Transport::Ws {
+                path: Some("/ws".to_string()),
+                host: Some("cdn.example.com".to_string()),
+            })
+        );
+        assert_eq!(
+            vmess_node.tls.as_ref().and_then(|tls| tls.sni.as_deref()),
+            Some("example.com")
+        );
+
+        let Node::Vless(vless_node) = &nodes[1] else {
+            panic!("expected a VLESS node");
+        };
+        assert_eq!(vless_node.flow.as_deref(), Some("xtls-rprx-vision"));
+        assert_eq!(vless_node.fingerprint.as_deref(), Some("chrome"));
+        assert_eq!(
+            vless_node.reality,
+            Some(RealityOptions {
+                public_key: "public_key".to_string(),
+                short_id: Some("0123".to_string()),
+            })
+        );
+
+        let Node::Trojan(trojan_node) = &nodes[2] else {
+            panic!("expected a Trojan node");
+        };
+        assert_eq!(trojan_node.password, "password");
+        assert_eq!(trojan_node.tls.insecure, Some(true));
+        assert_eq!(
+            trojan_node.transport,
+            Some(Transport::Grpc {
+                service_name: Some("grpc".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_tuic_socks_and_http_outbounds() {
+        let nodes = parse(
+            r#"{
+                "outbounds": [
+                    {
+                        "type": "tuic",
+                        "tag": "tuic",
+                        "server": "example.com",
+                        "server_port": 443,
+                        "uuid": "bf000d23-0752-40b4-affe-68f7707a9661",
+                        "password": "password",
+                        "congestion_control": "bbr",
+                        "udp_relay_mode": "quic",
+                        "tls": { "enabled": true, "alpn": ["h3"] }
+                    },
+                    {
+                        "type": "socks",
+                        "tag": "socks",
+                        "server": "127.0.0.1",
+                        "server_port": 1080,
+                        "version": "5",
+                        "username": "user",
+                        "password": "pass"
+                    },
+                    {
+                        "type": "http",
+                        "tag": "http",
+                        "server": "127.0.0.1",
+                        "server_port": 8080,
+                        "tls": { "enabled": true }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(nodes.len(), 3);
+
+        let Node::Tuic(tuic_node) = &nodes[0] else {
+            panic!("expected a TUIC node");
+        };
+        assert_eq!(
+            tuic_node.congestion_control,
+            Some(TuicCongestionControl::Bbr)
+        );
+        assert_eq!(tuic_node.udp_relay_mode, Some(TuicUdpRelayMode::Quic));
+        assert_eq!(tuic_node.tls.alpn, Some(vec!["h3".to_string()]));
+
+        let Node::Socks5(socks5_node) = &nodes[1] else {
+            panic!("expected a SOCKS5 node");
+        };
+        assert_eq!(socks5_node.username.as_deref(), Some("user"));
+        assert_eq!(socks5_node.password.as_deref(), Some("pass"));
+
+        let Node::Http(http_node) = &nodes[2] else {
+            panic!("expected a HTTP node");
+        };
+        assert_eq!(http_node.port, 8080);
+        assert!(http_node.tls.is_some());
+    }
+
+    #[test]
+    fn skip_invalid_outbounds() {
+        let nodes = parse(
+            r#"{
+                "outbounds": [
+                    {
+                        "type": "shadowsocks",
+                        "server": "example.com",
+                        "server_port": 8388,
+                        "method": "aes-256-gcm",
+                        "password": "password",
+                        "plugin": "obfs-local",
+                        "plugin_opts": "obfs=unknown"
+                    },
+                    {
+                        "type": "shadowsocks",
+                        "server": "example.com",
+                        "server_port": 8388,
+                        "method": "unknown-method",
+                        "password": "password"
+                    },
+                    {
+                        "type": "vless",
+                        "server": "example.com",
+                        "server_port": 443,
+                        "uuid": "not-a-uuid"
+                    },
+                    {
+                        "type": "tuic",
+                        "server": "example.com",
+                        "server_port": 443,
+                        "uuid": "bf000d23-0752-40b4-affe-68f7707a9661",
+                        "password": "password",
+                        "congestion_control": "unknown"
+                    },
+                    {
+                        "type": "socks",
+                        "server": "127.0.0.1",
+                        "server_port": 1080,
+                        "version": "4"
+                    },
+                    {
+                        "type": "hysteria",
+                        "server": "example.com",
+                        "server_port": 443
+                    },
+                    { "type": "shadowtls", "tag": "shadowtls", "server": "example.com" },
+                    {
+                        "type": "shadowsocks",
+                        "tag": "valid",
+                        "server": "example.com",
+                        "server_port": 8388,
+                        "method": "aes-256-gcm",
+                        "password": "password"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].get_display_name(), "valid");
+    }
+}
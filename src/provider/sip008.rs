@@ -0,0 +1,239 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::node::ss::{Method as SsMethod, Plugin as SsPlugin};
+use crate::node::{Node, SsNode};
+
+use super::{CommonProviderOptions, Provider, ProviderSource};
+
+/// SIP008 online configuration.
+/// Reference: https://shadowsocks.org/doc/sip008.html
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Sip008 {
+    /// Name of the SIP008 subscription.
+    pub name: Option<String>,
+
+    /// Where to load the SIP008 subscription from,
+    /// which could be a `url`, a local `path` or the inline `content`.
+    #[serde(flatten)]
+    pub source: ProviderSource,
+
+    /// Common provider options.
+    #[serde(flatten)]
+    pub options: CommonProviderOptions,
+}
+
+#[async_trait]
+impl Provider for Sip008 {
+    fn get_name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    fn get_source(&self) -> Option<&ProviderSource> {
+        Some(&self.source)
+    }
+
+    fn get_source_mut(&mut self) -> Option<&mut ProviderSource> {
+        Some(&mut self.source)
+    }
+
+    fn get_options(&self) -> &CommonProviderOptions {
+        &self.options
+    }
+
+    fn get_options_mut(&mut self) -> &mut CommonProviderOptions {
+        &mut self.options
+    }
+
+    fn parse_nodes_from_content(&self, content: Bytes) -> Result<Vec<Node>> {
+        parse_nodes(content, &self.get_display_name(), &self.options)
+    }
+}
+
+/// Parse the content of a SIP008 online configuration.
+/// `provider_name` is only used in log messages.
+pub(super) fn parse_nodes(
+    content: Bytes,
+    provider_name: &str,
+    options: &CommonProviderOptions,
+) -> Result<Vec<Node>> {
+    let sip008_config: Sip008Configuration = serde_json::from_slice(&content)
+        .context("failed to parse the provider content as SIP008 configuration json")?;
+
+    Ok(sip008_config
+        .servers
+        .into_iter()
+        .filter_map(|server| match sip008_server_to_node(server, options) {
+            Ok(node) => Some(node),
+            Err(err) => {
+                warn!(
+                    "failed to parse server in provider `{}`: {:#}, skip it",
+                    provider_name, err
+                );
+                None
+            }
+        })
+        .collect())
+}
+
+fn sip008_server_to_node(server: Sip008Server, options: &CommonProviderOptions) -> Result<Node> {
+    let name = server.remarks.as_deref().unwrap_or(&server.server);
+    let method = SsMethod::from_alias(&server.method)
+        .ok_or_else(|| anyhow!("Unknown method `{}` in server `{}`", server.method, name))?;
+    let plugin = server
+        .plugin
+        .filter(|plugin| !plugin.is_empty())
+        .map(|plugin| {
+            SsPlugin::from_name_and_opts_str(
+                plugin,
+                server.plugin_opts.as_deref().unwrap_or_default(),
+            )
+            .with_context(|| format!("failed to parse SS plugin of server `{name}`"))
+        })
+        .transpose()?;
+
+    Ok(Node::Ss(Box::new(SsNode {
+        id: server.id,
+        remarks: server.remarks,
+        server: server.server,
+        server_port: server.server_port,
+        password: server.password,
+        method,
+        udp: options.ss_udp,
+        udp_over_tcp: options.ss_udp_over_tcp,
+        plugin,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct Sip008Configuration {
+    #[allow(dead_code)]
+    version: u8,
+    servers: Vec<Sip008Server>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Sip008Server {
+    id: Option<Uuid>,
+    remarks: Option<String>,
+    server: String,
+    server_port: u16,
+    password: String,
+    method: String,
+    plugin: Option<String>,
+    plugin_opts: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::ss::{ObfsOpts, ObfsType};
+
+    // Reference: https://shadowsocks.org/doc/sip008.html
+    const CONFIGURATION: &str = r#"{
+    "version": 1,
+    "servers": [
+        {
+            "id": "27b8a625-4f4b-4428-9f0f-8a2317db7c79",
+            "remarks": "Name of the server",
+            "server": "example.com",
+            "server_port": 8388,
+            "password": "example",
+            "method": "chacha20-ietf-poly1305",
+            "plugin": "",
+            "plugin_opts": ""
+        },
+        {
+            "id": "7842c068-c667-41f2-8f7d-04feece3cb67",
+            "remarks": "Server with plugin",
+            "server": "example.com",
+            "server_port": 8389,
+            "password": "example",
+            "method": "aes-256-gcm",
+            "plugin": "obfs-local",
+            "plugin_opts": "obfs=tls;obfs-host=www.bing.com"
+        },
+        {
+            "remarks": "Unknown method",
+            "server": "example.com",
+            "server_port": 8390,
+            "password": "example",
+            "method": "unknown-method"
+        },
+        {
+            "remarks": "Invalid plugin options",
+            "server": "example.com",
+            "server_port": 8391,
+            "password": "example",
+            "method": "aes-256-gcm",
+            "plugin": "obfs-local",
+            "plugin_opts": "obfs=unknown"
+        }
+    ],
+    "bytes_used": 274877906944,
+    "bytes_remaining": 824633720832
+}"#;
+
+    fn parse_configuration(options: &CommonProviderOptions) -> Vec<Node> {
+        parse_nodes(Bytes::from(CONFIGURATION), "sip008", options).unwrap()
+    }
+
+    #[test]
+    fn parse_servers() {
+        let nodes = parse_configuration(&CommonProviderOptions::default());
+        assert_eq!(nodes.len(), 2);
+
+        let Node::Ss(ss_node) = &nodes[0] else {
+            panic!("expected a Shadowsocks node");
+        };
+        assert_eq!(
+            **ss_node,
+            SsNode {
+                id: Some(Uuid::parse_str("27b8a625-4f4b-4428-9f0f-8a2317db7c79").unwrap()),
+                remarks: Some(String::from("Name of the server")),
+                server: String::from("example.com"),
+                server_port: 8388,
+                password: String::from("example"),
+                method: SsMethod::AeadChacha20Poly1305,
+                udp: None,
+                udp_over_tcp: None,
+                plugin: None,
+            }
+        );
+
+        let Node::Ss(ss_node) = &nodes[1] else {
+            panic!("expected a Shadowsocks node");
+        };
+        assert_eq!(ss_node.remarks.as_deref(), Some("Server with plugin"));
+        assert_eq!(
+            ss_node.plugin,
+            Some(SsPlugin::SimpleObfs(ObfsOpts {
+                obfs: Some(ObfsType::Tls),
+                host: Some(String::from("www.bing.com")),
+                uri: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn apply_common_options() {
+        let nodes = parse_configuration(&CommonProviderOptions {
+            ss_udp: Some(false),
+            ss_udp_over_tcp: Some(true),
+            ..Default::default()
+        });
+
+        for node in &nodes {
+            let Node::Ss(ss_node) = node else {
+                panic!("expected a Shadowsocks node");
+            };
+            assert_eq!(ss_node.udp, Some(false));
+            assert_eq!(ss_node.udp_over_tcp, Some(true));
+        }
+    }
+}
@@ -3,15 +3,15 @@ use async_trait::async_trait;
 use base64_simd::STANDARD as base64;
 use base64_simd::STANDARD_NO_PAD as base64_no_pad;
 use bytes::Bytes;
-use http::Uri;
 use log::{debug, trace};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::node::{Node, SsNode, SsrNode};
+use crate::node::Node;
 
-use super::{CommonProviderOptions, Provider};
+use super::uri_list::parse_node_from_url;
+use super::{CommonProviderOptions, Provider, ProviderSource};
 
 /// SSR subscription.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,9 +20,10 @@ pub struct Ssr {
     /// Name of the SSR subscription.
     pub name: Option<String>,
 
-    /// URL of the SSR subscription.
-    #[serde(with = "http_serde::uri")]
-    pub url: Uri,
+    /// Where to load the SSR subscription from,
+    /// which could be a `url`, a local `path` or the inline `content`.
+    #[serde(flatten)]
+    pub source: ProviderSource,
 
     /// Common provider options.
     #[serde(flatten)]
@@ -35,82 +36,71 @@ impl Provider for Ssr {
         self.name.as_ref()
     }
 
-    fn get_url(&self) -> &Uri {
-        &self.url
+    fn get_source(&self) -> Option<&ProviderSource> {
+        Some(&self.source)
     }
 
-    fn set_url(&mut self, url: Uri) {
-        self.url = url;
+    fn get_source_mut(&mut self) -> Option<&mut ProviderSource> {
+        Some(&mut self.source)
     }
 
-    // Reference: https://github.com/shadowsocksr-backup/shadowsocks-rss/wiki/Subscribe-服务器订阅接口文档
-    fn parse_nodes_from_content(&self, content: Bytes) -> Result<Vec<Node>> {
-        let decoded_content = match base64
-            .decode_to_vec(&content)
-            .context("failed to decode base64 from the provider content")
-        {
-            Ok(decoded_content) => Ok(decoded_content),
-            Err(e) => {
-                // According to the SSR wiki, the base64 string should not be padded.
-                // However, some providers do not follow this rule, so we need to try
-                // to decode without padding.
-                debug!("failed to decode SSR subscription, now try to decode without padding");
-                if let Ok(decoded_content) = base64_no_pad.decode_to_vec(&content) {
-                    Ok(decoded_content)
-                } else {
-                    Err(e)
-                }
-            }
-        }?;
-        let decoded_string = String::from_utf8_lossy(&decoded_content);
-        trace!(
-            "decoded content of provider `{}`:\n{:?}",
-            self.url,
-            &decoded_string
-        );
-
-        decoded_string
-            .par_split('\n')
-            .filter_map(|line| {
-                if line.is_empty() {
-                    return None;
-                }
-
-                line.parse::<Url>().ok()
-            })
-            .map(|link| match link.scheme() {
-                "ss" => {
-                    let mut ss_node = SsNode::from_url(&link)
-                        .with_context(|| format!("failed to parse SS node from url:\n{link}"))?;
-
-                    if let Some(ss_udp) = self.options.ss_udp {
-                        ss_node.udp = Some(ss_udp);
-                    }
-
-                    if let Some(ss_uot) = self.options.ss_udp_over_tcp {
-                        ss_node.udp_over_tcp = Some(ss_uot);
-                    }
-
-                    Ok(Node::Ss(Box::new(ss_node)))
-                }
-
-                "ssr" => {
-                    let mut ssr_node = SsrNode::from_url(&link)
-                        .with_context(|| format!("failed to parse SSR node from URL:\n{link}"))?;
+    fn get_options(&self) -> &CommonProviderOptions {
+        &self.options
+    }
 
-                    if let Some(ssr_udpport) = self.options.ssr_udpport {
-                        ssr_node.udpport = Some(ssr_udpport);
-                    }
+    fn get_options_mut(&mut self) -> &mut CommonProviderOptions {
+        &mut self.options
+    }
 
-                    if let Some(ssr_uot) = self.options.ssr_uot {
-                        ssr_node.uot = Some(ssr_uot);
-                    }
+    fn parse_nodes_from_content(&self, content: Bytes) -> Result<Vec<Node>> {
+        parse_nodes(content, &self.get_display_name(), &self.options)
+    }
+}
 
-                    Ok(Node::Ssr(Box::new(ssr_node)))
-                }
+/// Parse the content of an SSR subscription.
+/// `provider_name` is only used in log messages.
+/// Reference: https://github.com/shadowsocksr-backup/shadowsocks-rss/wiki/Subscribe-服务器订阅接口文档
+pub(super) fn parse_nodes(
+    content: Bytes,
+    provider_name: &str,
+    options: &CommonProviderOptions,
+) -> Result<Vec<Node>> {
+    let decoded_content = match base64
+        .decode_to_vec(&content)
+        .context("failed to decode base64 from the provider content")
+    {
+        Ok(decoded_content) => Ok(decoded_content),
+        Err(e) => {
+            // According to the SSR wiki, the base64 string should not be padded.
+            // However, some providers do not follow this rule, so we need to try
+            // to decode without padding.
+            debug!("failed to decode SSR subscription, now try to decode without padding");
+            if let Ok(decoded_content) = base64_no_pad.decode_to_vec(&content) {
+                Ok(decoded_content)
+            } else {
+                Err(e)
+            }
+        }
+    }?;
+    let decoded_string = String::from_utf8_lossy(&decoded_content);
+    trace!(
+        "decoded content of provider `{}`:\n{:?}",
+        provider_name,
+        &decoded_string
+    );
+
+    decoded_string
+        .par_split('\n')
+        .filter_map(|line| {
+            if line.is_empty() {
+                return None;
+            }
 
-                _ => Err(anyhow!("Unknown scheme for `{}`", &link)),
-            })
-            .collect()
-    }
+            line.parse::<Url>().ok()
+        })
+        .map(|link| {
+            parse_node_from_url(&link, options)?
+                .ok_or_else(|| anyhow!("Unknown scheme for `{}`", &link))
+        })
+        .collect()
 }
@@ -0,0 +1,616 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::node::common::{ServerPort, TlsOptions};
+use crate::node::hysteria2::{Obfuscation as Hysteria2Obfuscation, Speed as Hysteria2Speed};
+use crate::node::snell::SnellObfsOpts;
+use crate::node::ss::{parse_obfs_plugin_args, Method as SsMethod, ObfsType, Plugin as SsPlugin};
+use crate::node::wireguard::{parse_addresses, parse_allowed_ips, parse_reserved};
+use crate::node::{Hysteria2Node, Node, SnellNode, SsNode, WireguardNode};
+
+use super::{CommonProviderOptions, Provider, ProviderSource};
+
+/// Surge profile.
+/// Only the `[Proxy]` and `[WireGuard *]` sections are used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Surge {
+    /// Name of the Surge profile.
+    pub name: Option<String>,
+
+    /// Where to load the Surge profile from,
+    /// which could be a `url`, a local `path` or the inline `content`.
+    #[serde(flatten)]
+    pub source: ProviderSource,
+
+    /// Common provider options.
+    #[serde(flatten)]
+    pub options: CommonProviderOptions,
+}
+
+#[async_trait]
+impl Provider for Surge {
+    fn get_name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    fn get_source(&self) -> Option<&ProviderSource> {
+        Some(&self.source)
+    }
+
+    fn get_source_mut(&mut self) -> Option<&mut ProviderSource> {
+        Some(&mut self.source)
+    }
+
+    fn get_options(&self) -> &CommonProviderOptions {
+        &self.options
+    }
+
+    fn get_options_mut(&mut self) -> &mut CommonProviderOptions {
+        &mut self.options
+    }
+
+    fn parse_nodes_from_content(&self, content: Bytes) -> Result<Vec<Node>> {
+        parse_nodes(content, &self.get_display_name(), &self.options)
+    }
+}
+
+/// Parse the content of a Surge profile.
+/// `provider_name` is only used in log messages.
+/// Reference: https://manual.nssurge.com/policy/proxy.html
+pub(super) fn parse_nodes(
+    content: Bytes,
+    provider_name: &str,
+    options: &CommonProviderOptions,
+) -> Result<Vec<Node>> {
+    let profile = String::from_utf8_lossy(&content);
+
+    let mut proxies: Vec<(String, String)> = Vec::new();
+    let mut wireguard_sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+    let mut current_section = SurgeSection::Other;
+    for line in profile.lines() {
+        let line = line.trim();
+        if line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with(';')
+            || line.starts_with("//")
+        {
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            let section = line[1..line.len() - 1].trim();
+            current_section = if section == "Proxy" {
+                SurgeSection::Proxy
+            } else if let Some(section_name) = section.strip_prefix("WireGuard ") {
+                let section_name = section_name.trim().to_string();
+                wireguard_sections.insert(section_name.clone(), HashMap::new());
+                SurgeSection::Wireguard(section_name)
+            } else {
+                SurgeSection::Other
+            };
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim().to_string(), value.trim().to_string());
+
+        match &current_section {
+            SurgeSection::Proxy => proxies.push((key, value)),
+            SurgeSection::Wireguard(section_name) => {
+                if let Some(section) = wireguard_sections.get_mut(section_name) {
+                    section.insert(key, value);
+                }
+            }
+            SurgeSection::Other => {}
+        }
+    }
+
+    Ok(proxies
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let mut params = split_params(&value).into_iter();
+            let proxy_type = params.next()?;
+            let (positional, named): (Vec<String>, Vec<String>) =
+                params.partition(|param| !param.contains('='));
+            let named: HashMap<String, String> = named
+                .into_iter()
+                .filter_map(|param| {
+                    let (key, value) = param.split_once('=')?;
+                    Some((key.trim().to_string(), unquote(value.trim()).to_string()))
+                })
+                .collect();
+
+            let node = match proxy_type.as_str() {
+                "ss" => parse_ss_node(name, &positional, &named, options),
+                "hysteria2" => parse_hysteria2_node(name, &positional, &named, provider_name),
+                "wireguard" => parse_wireguard_node(name, &named, &wireguard_sections),
+                "snell" => parse_snell_node(name, &positional, &named),
+                _ => {
+                    warn!(
+                        "unsupported proxy `{}` of type `{}` in provider `{}`, skip it",
+                        name, proxy_type, provider_name
+                    );
+                    return None;
+                }
+            };
+
+            match node {
+                Ok(node) => Some(node),
+                Err(err) => {
+                    warn!(
+                        "failed to parse proxy in provider `{}`: {:#}, skip it",
+                        provider_name, err
+                    );
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+fn parse_ss_node(
+    name: String,
+    positional: &[String],
+    named: &HashMap<String, String>,
+    options: &CommonProviderOptions,
+) -> Result<Node> {
+    let (server, port) = get_server_and_port(&name, positional)?;
+
+    let method_str = named
+        .get("encrypt-method")
+        .ok_or_else(|| anyhow!("proxy `{}` does not contain `encrypt-method`", name))?;
+    let method = SsMethod::from_alias(method_str)
+        .ok_or_else(|| anyhow!("Unknown method `{}` in proxy `{}`", method_str, name))?;
+
+    let password = named
+        .get("password")
+        .cloned()
+        .ok_or_else(|| anyhow!("proxy `{}` does not contain `password`", name))?;
+
+    let plugin = if named.contains_key("obfs") {
+        let obfs_opts: BTreeMap<String, String> = ["obfs", "obfs-host", "obfs-uri"]
+            .into_iter()
+            .filter_map(|key| Some((key.to_string(), named.get(key)?.clone())))
+            .collect();
+        Some(SsPlugin::SimpleObfs(
+            parse_obfs_plugin_args(&obfs_opts)
+                .with_context(|| format!("failed to parse obfs of proxy `{name}`"))?,
+        ))
+    } else {
+        None
+    };
+
+    Ok(Node::Ss(Box::new(SsNode {
+        id: None,
+        remarks: Some(name),
+        server,
+        server_port: port,
+        password,
+        method,
+        udp: options
+            .ss_udp
+            .or_else(|| named.get("udp-relay").map(|udp| udp == "true")),
+        udp_over_tcp: options.ss_udp_over_tcp,
+        plugin,
+    })))
+}
+
+fn parse_hysteria2_node(
+    name: String,
+    positional: &[String],
+    named: &HashMap<String, String>,
+    provider_name: &str,
+) -> Result<Node> {
+    let (server, port) = get_server_and_port(&name, positional)?;
+
+    // Surge hops between ports like `5000-6000;7044`,
+    // only the first port or range is used as a node holds a single one.
+    let port = match named.get("port-hopping") {
+        Some(port_hopping) => {
+            let mut ports = port_hopping.split(';');
+            let port =
+                ServerPort::from_port_str(ports.next().unwrap_or_default()).with_context(|| {
+                    format!("invalid `port-hopping` `{port_hopping}` of proxy `{name}`")
+                })?;
+
+            if ports.next().is_some() {
+                warn!(
+                    "proxy `{}` in provider `{}` hops between ports `{}`, only `{}` is used",
+                    name,
+                    provider_name,
+                    port_hopping,
+                    port_hopping.split(';').next().unwrap_or_default().trim()
+                );
+            }
+
+            port
+        }
+        None => ServerPort::Single(port),
+    };
+
+    let parse_bandwidth = |key: &str| {
+        named
+            .get(key)
+            .map(|bandwidth| {
+                bandwidth
+                    .parse()
+                    .map(Hysteria2Speed::Mbps)
+                    .with_context(|| format!("invalid `{key}` of proxy `{name}`"))
+            })
+            .transpose()
+    };
+    let up = parse_bandwidth("upload-bandwidth")?;
+    let down = parse_bandwidth("download-bandwidth")?;
+
+    Ok(Node::Hysteria2(Box::new(Hysteria2Node {
+        port,
+        up,
+        down,
+        auth: named.get("password").cloned(),
+        obfs: named
+            .get("salamander-password")
+            .map(|password| Hysteria2Obfuscation::Salamander {
+                password: password.clone(),
+            }),
+        tls: TlsOptions {
+            sni: named.get("sni").cloned(),
+            insecure: named
+                .get("skip-cert-verify")
+                .map(|insecure| insecure == "true"),
+            alpn: None,
+        },
+        remarks: Some(name),
+        server,
+    })))
+}
+
+fn parse_snell_node(
+    name: String,
+    positional: &[String],
+    named: &HashMap<String, String>,
+) -> Result<Node> {
+    let (server, port) = get_server_and_port(&name, positional)?;
+
+    let psk = named
+        .get("psk")
+        .cloned()
+        .ok_or_else(|| anyhow!("proxy `{}` does not contain `psk`", name))?;
+
+    let version = named
+        .get("version")
+        .map(|version| {
+            version
+                .parse()
+                .map_err(Into::into)
+                .and_then(SnellNode::check_version)
+        })
+        .transpose()
+        .with_context(|| format!("invalid `version` of proxy `{name}`"))?;
+
+    let obfs = match named.get("obfs").map(String::as_str) {
+        None => None,
+        Some(obfs) => Some(SnellObfsOpts {
+            mode: match obfs {
+                "http" => ObfsType::Http,
+                "tls" => ObfsType::Tls,
+                _ => return Err(anyhow!("Unknown obfs `{}` in proxy `{}`", obfs, name)),
+            },
+            host: named.get("obfs-host").cloned(),
+        }),
+    };
+
+    Ok(Node::Snell(Box::new(SnellNode {
+        remarks: Some(name),
+        server,
+        port,
+        psk,
+        version,
+        obfs,
+    })))
+}
+
+fn parse_wireguard_node(
+    name: String,
+    named: &HashMap<String, String>,
+    wireguard_sections: &HashMap<String, HashMap<String, String>>,
+) -> Result<Node> {
+    let section_name = named
+        .get("section-name")
+        .ok_or_else(|| anyhow!("proxy `{}` does not contain `section-name`", name))?;
+    let section = wireguard_sections.get(section_name).ok_or_else(|| {
+        anyhow!(
+            "section `[WireGuard {}]` of proxy `{}` does not exist",
+            section_name,
+            name
+        )
+    })?;
+
+    let private_key = section
+        .get("private-key")
+        .cloned()
+        .ok_or_else(|| anyhow!("`private-key` is missing in `[WireGuard {}]`", section_name))?;
+
+    let ip = section
+        .get("self-ip")
+        .map(|ip| parse_addresses(ip))
+        .transpose()?
+        .and_then(|(ip, _)| ip);
+    let ipv6 = section
+        .get("self-ip-v6")
+        .map(|ipv6| parse_addresses(ipv6))
+        .transpose()?
+        .and_then(|(_, ipv6)| ipv6);
+
+    // Only the first peer is used.
+    let peer = section
+        .get("peer")
+        .ok_or_else(|| anyhow!("`peer` is missing in `[WireGuard {}]`", section_name))?;
+    let peer: HashMap<String, String> = split_params(
+        peer.trim()
+            .trim_start_matches('(')
+            .split(')')
+            .next()
+            .unwrap_or_default(),
+    )
+    .into_iter()
+    .filter_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        Some((key.trim().to_string(), unquote(value.trim()).to_string()))
+    })
+    .collect();
+
+    let public_key = peer
+        .get("public-key")
+        .cloned()
+        .ok_or_else(|| anyhow!("`public-key` is missing in the peer of `{}`", name))?;
+
+    let endpoint = peer
+        .get("endpoint")
+        .ok_or_else(|| anyhow!("`endpoint` is missing in the peer of `{}`", name))?;
+    let (server, port) = endpoint
+        .rsplit_once(':')
+        .ok_or_else(|| anyhow!("invalid endpoint `{}`", endpoint))?;
+    let port = port
+        .parse()
+        .with_context(|| format!("invalid port in endpoint `{endpoint}`"))?;
+
+    let reserved = peer
+        .get("client-id")
+        .map(|client_id| parse_reserved(&client_id.replace('/', ",")))
+        .transpose()?;
+
+    Ok(Node::Wireguard(WireguardNode {
+        remarks: Some(name),
+        server: server
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string(),
+        port,
+        ip,
+        ipv6,
+        private_key,
+        public_key,
+        pre_shared_key: peer.get("preshared-key").cloned(),
+        reserved,
+        allowed_ips: peer
+            .get("allowed-ips")
+            .map(|allowed_ips| parse_allowed_ips(allowed_ips)),
+    }))
+}
+
+fn get_server_and_port(name: &str, positional: &[String]) -> Result<(String, u16)> {
+    let server = positional
+        .first()
+        .ok_or_else(|| anyhow!("proxy `{}` does not contain server", name))?
+        .to_string();
+    let port = positional
+        .get(1)
+        .ok_or_else(|| anyhow!("proxy `{}` does not contain port", name))?
+        .parse()
+        .with_context(|| format!("invalid port in proxy `{name}`"))?;
+
+    Ok((server, port))
+}
+
+/// Split the parameters by commas, but ignore the commas inside quotes or parentheses.
+fn split_params(s: &str) -> Vec<String> {
+    let mut params = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut parentheses_depth = 0u32;
+
+    for c in s.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '(' if !in_quotes => parentheses_depth += 1,
+            ')' if !in_quotes => parentheses_depth = parentheses_depth.saturating_sub(1),
+            ',' if !in_quotes && parentheses_depth == 0 => {
+                params.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        params.push(current.trim().to_string());
+    }
+
+    params
+}
+
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+enum SurgeSection {
+    Proxy,
+    Wireguard(String),
+    Other,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::GetNodeName;
+
+    const PROFILE: &str = r#"
+[General]
+loglevel = notify
+
+[Proxy]
+SS = ss, ss.example.com, 8388, encrypt-method=aes-256-gcm, password="pass,word", obfs=http, obfs-host=bing.com, udp-relay=true
+HY2 = hysteria2, hy2.example.com, 443, password=auth, sni=real.example.com, skip-cert-verify=true, download-bandwidth=100
+WG = wireguard, section-name=Home
+Snell = snell, snell.example.com, 44046, psk=yourpsk, version=4, obfs=tls, obfs-host=bing.com
+HY2-Hopping = hysteria2, hy2.example.com, 443, password=auth, port-hopping="5000-6000;7044", salamander-password=obfs, upload-bandwidth=20, download-bandwidth=100
+SS-Broken = ss, ss.example.com, 8388, password=pass
+VMess = vmess, vmess.example.com, 443, username=b831381d-6324-4d53-ad4f-8cda48b30811
+
+[WireGuard Home]
+private-key = yAnz5TF+lXXJte14tji3zlMNq+hdZvsXnrqlLzVrd0k=
+self-ip = 172.16.0.2
+self-ip-v6 = 2606:4700:110:8a36::2
+peer = (public-key = bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo=, allowed-ips = "0.0.0.0/0, ::/0", endpoint = 162.159.192.1:2408, client-id = 1/2/3)
+"#;
+
+    fn parse_profile() -> Vec<Node> {
+        let provider = Surge {
+            name: None,
+            source: ProviderSource::Content(String::new()),
+            options: CommonProviderOptions::default(),
+        };
+        provider
+            .parse_nodes_from_content(Bytes::from(PROFILE))
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_ss_with_obfs() {
+        let nodes = parse_profile();
+        let Node::Ss(ss_node) = &nodes[0] else {
+            panic!("expected a SS node, got {:?}", nodes[0]);
+        };
+
+        assert_eq!(ss_node.remarks.as_deref(), Some("SS"));
+        assert_eq!(ss_node.server, "ss.example.com");
+        assert_eq!(ss_node.server_port, 8388);
+        assert_eq!(ss_node.method, SsMethod::AeadAes256Gcm);
+        assert_eq!(ss_node.password, "pass,word");
+        assert_eq!(ss_node.udp, Some(true));
+        let Some(SsPlugin::SimpleObfs(obfs_opts)) = &ss_node.plugin else {
+            panic!("expected the obfs plugin, got {:?}", ss_node.plugin);
+        };
+        assert_eq!(obfs_opts.obfs, Some(ObfsType::Http));
+        assert_eq!(obfs_opts.host.as_deref(), Some("bing.com"));
+    }
+
+    #[test]
+    fn parse_hysteria2() {
+        let nodes = parse_profile();
+        let Node::Hysteria2(hysteria2_node) = &nodes[1] else {
+            panic!("expected a Hysteria2 node, got {:?}", nodes[1]);
+        };
+
+        assert_eq!(hysteria2_node.remarks.as_deref(), Some("HY2"));
+        assert_eq!(hysteria2_node.server, "hy2.example.com");
+        assert_eq!(hysteria2_node.get_port(), 443);
+        assert_eq!(hysteria2_node.auth.as_deref(), Some("auth"));
+        assert_eq!(
+            hysteria2_node.down.as_ref().and_then(|down| down.to_mbps()),
+            Some(100)
+        );
+        assert_eq!(hysteria2_node.tls.sni.as_deref(), Some("real.example.com"));
+        assert_eq!(hysteria2_node.tls.insecure, Some(true));
+    }
+
+    #[test]
+    fn parse_wireguard_with_section_name() {
+        let nodes = parse_profile();
+        let Node::Wireguard(wireguard_node) = &nodes[2] else {
+            panic!("expected a WireGuard node, got {:?}", nodes[2]);
+        };
+
+        assert_eq!(wireguard_node.remarks.as_deref(), Some("WG"));
+        assert_eq!(wireguard_node.server, "162.159.192.1");
+        assert_eq!(wireguard_node.port, 2408);
+        assert_eq!(wireguard_node.ip, Some("172.16.0.2".parse().unwrap()));
+        assert_eq!(
+            wireguard_node.ipv6,
+            Some("2606:4700:110:8a36::2".parse().unwrap())
+        );
+        assert_eq!(
+            wireguard_node.private_key,
+            "yAnz5TF+lXXJte14tji3zlMNq+hdZvsXnrqlLzVrd0k="
+        );
+        assert_eq!(
+            wireguard_node.public_key,
+            "bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo="
+        );
+        assert_eq!(wireguard_node.reserved, Some([1, 2, 3]));
+    }
+
+    #[test]
+    fn parse_snell() {
+        let nodes = parse_profile();
+        let Node::Snell(snell_node) = &nodes[3] else {
+            panic!("expected a Snell node, got {:?}", nodes[3]);
+        };
+
+        assert_eq!(
+            **snell_node,
+            SnellNode {
+                remarks: Some(String::from("Snell")),
+                server: String::from("snell.example.com"),
+                port: 44046,
+                psk: String::from("yourpsk"),
+                version: Some(4),
+                obfs: Some(SnellObfsOpts {
+                    mode: ObfsType::Tls,
+                    host: Some(String::from("bing.com")),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_hysteria2_with_port_hopping_and_obfs() {
+        let nodes = parse_profile();
+        let Node::Hysteria2(hysteria2_node) = &nodes[4] else {
+            panic!("expected a Hysteria2 node, got {:?}", nodes[4]);
+        };
+
+        assert_eq!(
+            **hysteria2_node,
+            Hysteria2Node {
+                remarks: Some(String::from("HY2-Hopping")),
+                server: String::from("hy2.example.com"),
+                port: ServerPort::Range(5000, 6000),
+                auth: Some(String::from("auth")),
+                obfs: Some(Hysteria2Obfuscation::Salamander {
+                    password: String::from("obfs"),
+                }),
+                up: Some(Hysteria2Speed::Mbps(20)),
+                down: Some(Hysteria2Speed::Mbps(100)),
+                tls: TlsOptions::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn skip_unsupported_and_invalid_proxies() {
+        assert_eq!(parse_profile().len(), 5);
+    }
+}
@@ -0,0 +1,290 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64_simd::{forgiving_decode_to_vec, URL_SAFE_NO_PAD as base64_url_no_pad};
+use bytes::Bytes;
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::node::{
+    HttpNode, Hysteria2Node, HysteriaNode, Node, Socks5Node, SsNode, SsrNode, TrojanNode, TuicNode,
+    VlessNode, VmessNode, WireguardNode,
+};
+
+use super::{CommonProviderOptions, Provider, ProviderSource};
+
+/// A subscription of share links, one link per line.
+/// The content could be plain text, or encoded with base64 (padded or not).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UriList {
+    /// Name of the subscription.
+    pub name: Option<String>,
+
+    /// Where to load the subscription from,
+    /// which could be a `url`, a local `path` or the inline `content`.
+    #[serde(flatten)]
+    pub source: ProviderSource,
+
+    /// Common provider options.
+    #[serde(flatten)]
+    pub options: CommonProviderOptions,
+}
+
+#[async_trait]
+impl Provider for UriList {
+    fn get_name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    fn get_source(&self) -> Option<&ProviderSource> {
+        Some(&self.source)
+    }
+
+    fn get_source_mut(&mut self) -> Option<&mut ProviderSource> {
+        Some(&mut self.source)
+    }
+
+    fn get_options(&self) -> &CommonProviderOptions {
+        &self.options
+    }
+
+    fn get_options_mut(&mut self) -> &mut CommonProviderOptions {
+        &mut self.options
+    }
+
+    fn parse_nodes_from_content(&self, content: Bytes) -> Result<Vec<Node>> {
+        parse_nodes(content, &self.get_display_name(), &self.options)
+    }
+}
+
+/// Parse the content of a share link subscription.
+/// `provider_name` is only used in log messages.
+pub(super) fn parse_nodes(
+    content: Bytes,
+    provider_name: &str,
+    options: &CommonProviderOptions,
+) -> Result<Vec<Node>> {
+    let content = String::from_utf8_lossy(&content);
+    let content = content.trim();
+
+    // Plain share links always contain `://`, which is not valid in base64.
+    let decoded_content = if content.contains("://") {
+        content.to_string()
+    } else {
+        debug!(
+            "content of provider `{}` is not plain text, try to decode it as base64",
+            provider_name
+        );
+        let content_without_whitespaces: String = content.split_ascii_whitespace().collect();
+        let decoded_content = forgiving_decode_to_vec(content_without_whitespaces.as_bytes())
+            .or_else(|_| base64_url_no_pad.decode_to_vec(&content_without_whitespaces))
+            .context("failed to decode base64 from the provider content")?;
+        String::from_utf8_lossy(&decoded_content).to_string()
+    };
+    trace!(
+        "decoded content of provider `{}`:\n{:?}",
+        provider_name,
+        &decoded_content
+    );
+
+    decoded_content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| match line.parse::<Url>() {
+            Ok(link) => Some(link),
+            Err(err) => {
+                warn!(
+                    "failed to parse `{}` as URL in provider `{}`: {}, skip it",
+                    line, provider_name, err
+                );
+                None
+            }
+        })
+        .filter_map(|link| match parse_node_from_url(&link, options) {
+            Ok(Some(node)) => Some(Ok(node)),
+            Ok(None) => {
+                warn!(
+                    "unknown scheme `{}` in provider `{}`, skip it",
+                    link.scheme(),
+                    provider_name
+                );
+                None
+            }
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
+/// Parse a share link to a node by its scheme,
+/// and apply the common provider options to it.
+/// Returns `None` if the scheme is unknown.
+pub(super) fn parse_node_from_url(
+    link: &Url,
+    options: &CommonProviderOptions,
+) -> Result<Option<Node>> {
+    match link.scheme() {
+        "ss" => {
+            let mut ss_node = SsNode::from_url(link)
+                .with_context(|| format!("failed to parse SS node from url:\n{link}"))?;
+
+            if let Some(ss_udp) = options.ss_udp {
+                ss_node.udp = Some(ss_udp);
+            }
+
+            if let Some(ss_uot) = options.ss_udp_over_tcp {
+                ss_node.udp_over_tcp = Some(ss_uot);
+            }
+
+            Ok(Some(Node::Ss(Box::new(ss_node))))
+        }
+
+        "ssr" => {
+            let mut ssr_node = SsrNode::from_url(link)
+                .with_context(|| format!("failed to parse SSR node from URL:\n{link}"))?;
+
+            if let Some(ssr_udpport) = options.ssr_udpport {
+                ssr_node.udpport = Some(ssr_udpport);
+            }
+
+            if let Some(ssr_uot) = options.ssr_uot {
+                ssr_node.uot = Some(ssr_uot);
+            }
+
+            Ok(Some(Node::Ssr(Box::new(ssr_node))))
+        }
+
+        "vmess" => {
+            let vmess_node = VmessNode::from_url(link)
+                .with_context(|| format!("failed to parse VMess node from URL:\n{link}"))?;
+
+            Ok(Some(Node::Vmess(Box::new(vmess_node))))
+        }
+
+        "vless" => {
+            let vless_node = VlessNode::from_url(link)
+                .with_context(|| format!("failed to parse VLESS node from URL:\n{link}"))?;
+
+            Ok(Some(Node::Vless(Box::new(vless_node))))
+        }
+
+        "trojan" => {
+            let trojan_node = TrojanNode::from_url(link)
+                .with_context(|| format!("failed to parse Trojan node from URL:\n{link}"))?;
+
+            Ok(Some(Node::Trojan(Box::new(trojan_node))))
+        }
+
+        "tuic" => {
+            let tuic_node = TuicNode::from_url(link)
+                .with_context(|| format!("failed to parse TUIC node from URL:\n{link}"))?;
+
+            Ok(Some(Node::Tuic(Box::new(tuic_node))))
+        }
+
+        "hysteria" => {
+            let hysteria_node = HysteriaNode::from_url(link)
+                .with_context(|| format!("failed to parse Hysteria node from URL:\n{link}"))?;
+
+            Ok(Some(Node::Hysteria(Box::new(hysteria_node))))
+        }
+
+        "hysteria2" | "hy2" => {
+            let hysteria2_node = Hysteria2Node::from_url(link)
+                .with_context(|| format!("failed to parse Hysteria 2 node from URL:\n{link}"))?;
+
+            Ok(Some(Node::Hysteria2(Box::new(hysteria2_node))))
+        }
+
+        "wireguard" | "wg" => {
+            let wireguard_node = WireguardNode::from_url(link)
+                .with_context(|| format!("failed to parse WireGuard node from URL:\n{link}"))?;
+
+            Ok(Some(Node::Wireguard(wireguard_node)))
+        }
+
+        "socks5" => {
+            let socks5_node = Socks5Node::from_url(link)
+                .with_context(|| format!("failed to parse SOCKS5 node from URL:\n{link}"))?;
+
+            Ok(Some(Node::Socks5(Box::new(socks5_node))))
+        }
+
+        "http" | "https" => {
+            let http_node = HttpNode::from_url(link)
+                .with_context(|| format!("failed to parse HTTP node from URL:\n{link}"))?;
+
+            Ok(Some(Node::Http(Box::new(http_node))))
+        }
+
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64_simd::STANDARD as base64;
+
+    use super::*;
+    use crate::node::GetNodeName;
+
+    const LINKS: &str = "ss://YWVzLTEyOC1nY206dGVzdA@192.168.100.1:8888#SS\n\
+        trojan://password@example.com:443?sni=example.com#Trojan~~~";
+
+    fn parse(content: impl Into<Bytes>) -> Vec<String> {
+        let provider = UriList {
+            name: None,
+            source: ProviderSource::Content(String::new()),
+            options: CommonProviderOptions::default(),
+        };
+        provider
+            .parse_nodes_from_content(content.into())
+            .unwrap()
+            .iter()
+            .map(|node| node.get_display_name())
+            .collect()
+    }
+
+    #[test]
+    fn parse_plain_uri_list() {
+        assert_eq!(parse(LINKS), vec!["SS", "Trojan~~~"]);
+    }
+
+    #[test]
+    fn parse_padded_base64_uri_list() {
+        let content = base64.encode_to_string(LINKS);
+        assert!(content.ends_with('='));
+
+        assert_eq!(parse(content), vec!["SS", "Trojan~~~"]);
+    }
+
+    #[test]
+    fn parse_unpadded_url_safe_base64_uri_list() {
+        let content = base64_url_no_pad.encode_to_string(LINKS);
+        assert!(content.contains(['-', '_']) && !content.ends_with('='));
+
+        assert_eq!(parse(content), vec!["SS", "Trojan~~~"]);
+    }
+
+    #[test]
+    fn parse_uri_list_with_crlf() {
+        assert_eq!(parse(LINKS.replace('\n', "\r\n")), vec!["SS", "Trojan~~~"]);
+
+        // Line breaks inside base64 are ignored as well.
+        let content = base64.encode_to_string(LINKS);
+        let (first, second) = content.split_at(20);
+        assert_eq!(
+            parse(format!("{first}\r\n{second}\r\n")),
+            vec!["SS", "Trojan~~~"]
+        );
+    }
+
+    #[test]
+    fn skip_unknown_scheme() {
+        assert_eq!(
+            parse(format!("unknown://example.com:443#Unknown\n{LINKS}")),
+            vec!["SS", "Trojan~~~"]
+        );
+    }
+}
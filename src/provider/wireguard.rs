@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::node::{Node, WireguardNode};
+
+use super::{CommonProviderOptions, Provider, ProviderSource};
+
+/// WireGuard configuration in the wg-quick format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Wireguard {
+    /// Name of the WireGuard configuration.
+    /// It is also used as the name of the nodes.
+    pub name: Option<String>,
+
+    /// Where to load the WireGuard configuration from,
+    /// which could be a `url`, a local `path` or the inline `content`.
+    #[serde(flatten)]
+    pub source: ProviderSource,
+
+    /// Common provider options.
+    #[serde(flatten)]
+    pub options: CommonProviderOptions,
+}
+
+#[async_trait]
+impl Provider for Wireguard {
+    fn get_name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    fn get_source(&self) -> Option<&ProviderSource> {
+        Some(&self.source)
+    }
+
+    fn get_source_mut(&mut self) -> Option<&mut ProviderSource> {
+        Some(&mut self.source)
+    }
+
+    fn get_options(&self) -> &CommonProviderOptions {
+        &self.options
+    }
+
+    fn get_options_mut(&mut self) -> &mut CommonProviderOptions {
+        &mut self.options
+    }
+
+    fn parse_nodes_from_content(&self, content: Bytes) -> Result<Vec<Node>> {
+        // wg-quick configurations do not contain names, so we use the provider name,
+        // or the file name of the configuration (e.g. `warp` in `warp.conf`).
+        let name = self.name.clone().unwrap_or_else(|| {
+            let file_name = match &self.source {
+                ProviderSource::Url(url) => url.path().rsplit('/').next().map(String::from),
+                ProviderSource::Path(path) => path
+                    .file_name()
+                    .map(|file_name| file_name.to_string_lossy().to_string()),
+                ProviderSource::Content(_) => None,
+            }
+            .filter(|file_name| !file_name.is_empty())
+            .unwrap_or_else(|| String::from("WireGuard"));
+            file_name
+                .strip_suffix(".conf")
+                .unwrap_or(&file_name)
+                .to_string()
+        });
+
+        parse_nodes(content, &name)
+    }
+}
+
+/// Parse the content of a wg-quick configuration,
+/// the nodes are named after `name`, followed by an index if there are multiple peers.
+pub(super) fn parse_nodes(content: Bytes, name: &str) -> Result<Vec<Node>> {
+    let conf = String::from_utf8_lossy(&content);
+    let mut nodes = WireguardNode::from_wg_quick_conf(&conf)
+        .context("failed to parse the provider content as wg-quick configuration")?;
+
+    let nodes_len = nodes.len();
+    for (index, node) in nodes.iter_mut().enumerate() {
+        node.remarks = Some(if nodes_len == 1 {
+            name.to_string()
+        } else {
+            format!("{} {}", name, index + 1)
+        });
+    }
+
+    Ok(nodes.into_iter().map(Node::Wireguard).collect())
+}
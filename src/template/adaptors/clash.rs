@@ -1,7 +1,10 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use serde_yaml::to_string;
 
+use crate::node::common::Transport;
 use crate::node::ss::{ObfsOpts, ObfsType, Plugin as SsPlugin};
 use crate::node::{GetNodeName, Node};
 
@@ -38,6 +41,71 @@ pub enum ClashProxy {
         protocol_param: Option<String>,
         udp: Option<bool>,
     },
+
+    #[serde(rename = "vmess", rename_all = "kebab-case")]
+    Vmess {
+        name: String,
+        server: String,
+        port: u16,
+        uuid: String,
+        #[serde(rename = "alterId")]
+        alter_id: u16,
+        cipher: String,
+        udp: Option<bool>,
+        tls: Option<bool>,
+        skip_cert_verify: Option<bool>,
+        servername: Option<String>,
+        #[serde(flatten)]
+        transport: Option<ClashTransport>,
+    },
+
+    #[serde(rename = "trojan", rename_all = "kebab-case")]
+    Trojan {
+        name: String,
+        server: String,
+        port: u16,
+        password: String,
+        udp: Option<bool>,
+        sni: Option<String>,
+        alpn: Option<Vec<String>>,
+        skip_cert_verify: Option<bool>,
+        #[serde(flatten)]
+        transport: Option<ClashTransport>,
+    },
+
+    #[serde(rename = "socks5", rename_all = "kebab-case")]
+    Socks5 {
+        name: String,
+        server: String,
+        port: u16,
+        username: Option<String>,
+        password: Option<String>,
+        tls: Option<bool>,
+        skip_cert_verify: Option<bool>,
+        udp: Option<bool>,
+    },
+
+    #[serde(rename = "http", rename_all = "kebab-case")]
+    Http {
+        name: String,
+        server: String,
+        port: u16,
+        username: Option<String>,
+        password: Option<String>,
+        tls: Option<bool>,
+        skip_cert_verify: Option<bool>,
+        sni: Option<String>,
+    },
+
+    #[serde(rename = "snell", rename_all = "kebab-case")]
+    Snell {
+        name: String,
+        server: String,
+        port: u16,
+        psk: String,
+        version: Option<u8>,
+        obfs_opts: Option<ClashSsPluginObfsOpts>,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -126,6 +194,89 @@ impl From<ClashSsPluginObfsType> for ObfsType {
     }
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "network", rename_all = "kebab-case")]
+pub enum ClashTransport {
+    #[serde(rename_all = "kebab-case")]
+    Ws { ws_opts: Option<ClashWsOpts> },
+    #[serde(rename_all = "kebab-case")]
+    H2 { h2_opts: Option<ClashH2Opts> },
+    #[serde(rename_all = "kebab-case")]
+    Grpc { grpc_opts: Option<ClashGrpcOpts> },
+}
+impl From<&Transport> for ClashTransport {
+    fn from(value: &Transport) -> Self {
+        match value {
+            Transport::Ws { path, host } => Self::Ws {
+                ws_opts: Some(ClashWsOpts {
+                    path: path.clone(),
+                    headers: host
+                        .as_ref()
+                        .map(|host| BTreeMap::from([(String::from("Host"), host.clone())])),
+                }),
+            },
+            Transport::H2 { path, host } => Self::H2 {
+                h2_opts: Some(ClashH2Opts {
+                    host: host.clone(),
+                    path: path.clone(),
+                }),
+            },
+            Transport::Grpc { service_name } => Self::Grpc {
+                grpc_opts: Some(ClashGrpcOpts {
+                    grpc_service_name: service_name.clone(),
+                }),
+            },
+        }
+    }
+}
+impl From<ClashTransport> for Transport {
+    fn from(value: ClashTransport) -> Self {
+        match value {
+            ClashTransport::Ws { ws_opts } => {
+                let ws_opts = ws_opts.unwrap_or_default();
+                Self::Ws {
+                    path: ws_opts.path,
+                    host: ws_opts
+                        .headers
+                        .and_then(|mut headers| headers.remove("Host")),
+                }
+            }
+            ClashTransport::H2 { h2_opts } => {
+                let h2_opts = h2_opts.unwrap_or_default();
+                Self::H2 {
+                    path: h2_opts.path,
+                    host: h2_opts.host,
+                }
+            }
+            ClashTransport::Grpc { grpc_opts } => Self::Grpc {
+                service_name: grpc_opts.and_then(|grpc_opts| grpc_opts.grpc_service_name),
+            },
+        }
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ClashWsOpts {
+    pub path: Option<String>,
+    pub headers: Option<BTreeMap<String, String>>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ClashH2Opts {
+    pub host: Option<Vec<String>>,
+    pub path: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ClashGrpcOpts {
+    pub grpc_service_name: Option<String>,
+}
+
 #[derive(Default)]
 pub struct Clash;
 impl Adaptor for Clash {
@@ -183,6 +334,87 @@ impl Adaptor for Clash {
             Node::Hysteria2(_) => None,
 
             Node::Wireguard(_) => None,
+
+            Node::Vmess(vmess_node) => Some(ClashProxy::Vmess {
+                name: vmess_node.get_display_name(),
+                server: vmess_node.server.clone(),
+                port: vmess_node.port,
+                uuid: vmess_node.uuid.to_string(),
+                alter_id: vmess_node.alter_id.unwrap_or(0),
+                cipher: vmess_node
+                    .security
+                    .map_or("auto", |security| security.get_alias())
+                    .to_string(),
+                udp: None,
+                tls: vmess_node.tls.as_ref().map(|_| true),
+                skip_cert_verify: vmess_node.tls.as_ref().and_then(|tls| tls.insecure),
+                servername: vmess_node.tls.as_ref().and_then(|tls| tls.sni.clone()),
+                transport: vmess_node.transport.as_ref().map(Into::into),
+            }),
+
+            Node::Vless(_) => None,
+
+            Node::Trojan(trojan_node) => {
+                // Clash only supports WebSocket and gRPC as the transport of Trojan.
+                if matches!(trojan_node.transport, Some(Transport::H2 { .. })) {
+                    return None;
+                }
+
+                Some(ClashProxy::Trojan {
+                    name: trojan_node.get_display_name(),
+                    server: trojan_node.server.clone(),
+                    port: trojan_node.port,
+                    password: trojan_node.password.clone(),
+                    udp: None,
+                    sni: trojan_node.tls.sni.clone(),
+                    alpn: trojan_node.tls.alpn.clone(),
+                    skip_cert_verify: trojan_node.tls.insecure,
+                    transport: trojan_node.transport.as_ref().map(Into::into),
+                })
+            }
+
+            Node::Tuic(_) => None,
+
+            Node::Socks5(socks5_node) => Some(ClashProxy::Socks5 {
+                name: socks5_node.get_display_name(),
+                server: socks5_node.server.clone(),
+                port: socks5_node.port,
+                username: socks5_node.username.clone(),
+                password: socks5_node.password.clone(),
+                tls: socks5_node.tls.as_ref().map(|_| true),
+                skip_cert_verify: socks5_node.tls.as_ref().and_then(|tls| tls.insecure),
+                udp: None,
+            }),
+
+            Node::Http(http_node) => Some(ClashProxy::Http {
+                name: http_node.get_display_name(),
+                server: http_node.server.clone(),
+                port: http_node.port,
+                username: http_node.username.clone(),
+                password: http_node.password.clone(),
+                tls: http_node.tls.as_ref().map(|_| true),
+                skip_cert_verify: http_node.tls.as_ref().and_then(|tls| tls.insecure),
+                sni: http_node.tls.as_ref().and_then(|tls| tls.sni.clone()),
+            }),
+
+            Node::Snell(snell_node) => {
+                // Clash only supports Snell v1 to v3.
+                if matches!(snell_node.version, Some(version) if version > 3) {
+                    return None;
+                }
+
+                Some(ClashProxy::Snell {
+                    name: snell_node.get_display_name(),
+                    server: snell_node.server.clone(),
+                    port: snell_node.port,
+                    psk: snell_node.psk.clone(),
+                    version: snell_node.version,
+                    obfs_opts: snell_node.obfs.as_ref().map(|obfs| ClashSsPluginObfsOpts {
+                        mode: Some(obfs.mode.into()),
+                        host: obfs.host.clone(),
+                    }),
+                })
+            }
         }
     }
 
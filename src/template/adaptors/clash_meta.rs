@@ -1,13 +1,15 @@
+use std::collections::BTreeMap;
+
 use base64_simd::STANDARD as base64;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use serde_yaml::to_string;
 
+use crate::node::common::Transport;
 use crate::node::hysteria::{ServerPort as HysteriaServerPort, Speed as HysteriaSpeed};
 use crate::node::hysteria2::{
     Obfuscation as Hysteria2Obfuscation, ServerPort as Hysteria2ServerPort,
 };
-
 use crate::node::ss::{ObfsOpts, ObfsType, Plugin as SsPlugin};
 use crate::node::{GetNodeName, Node};
 
@@ -95,6 +97,104 @@ pub enum ClashMetaProxy<'a> {
         udp: Option<bool>,
         persistent_keepalive: Option<u32>,
     },
+
+    #[serde(rename = "vmess", rename_all = "kebab-case")]
+    Vmess {
+        name: String,
+        server: &'a str,
+        port: u16,
+        uuid: String,
+        #[serde(rename = "alterId")]
+        alter_id: u16,
+        cipher: &'a str,
+        udp: Option<bool>,
+        tls: Option<bool>,
+        skip_cert_verify: Option<bool>,
+        servername: Option<&'a str>,
+        alpn: Option<&'a [String]>,
+        #[serde(flatten)]
+        transport: Option<ClashMetaTransport<'a>>,
+    },
+
+    #[serde(rename = "vless", rename_all = "kebab-case")]
+    Vless {
+        name: String,
+        server: &'a str,
+        port: u16,
+        uuid: String,
+        flow: Option<&'a str>,
+        udp: Option<bool>,
+        tls: Option<bool>,
+        servername: Option<&'a str>,
+        skip_cert_verify: Option<bool>,
+        alpn: Option<&'a [String]>,
+        client_fingerprint: Option<&'a str>,
+        reality_opts: Option<ClashMetaRealityOpts<'a>>,
+        #[serde(flatten)]
+        transport: Option<ClashMetaTransport<'a>>,
+    },
+
+    #[serde(rename = "trojan", rename_all = "kebab-case")]
+    Trojan {
+        name: String,
+        server: &'a str,
+        port: u16,
+        password: &'a str,
+        udp: Option<bool>,
+        sni: Option<&'a str>,
+        alpn: Option<&'a [String]>,
+        skip_cert_verify: Option<bool>,
+        #[serde(flatten)]
+        transport: Option<ClashMetaTransport<'a>>,
+    },
+
+    #[serde(rename = "tuic", rename_all = "kebab-case")]
+    Tuic {
+        name: String,
+        server: &'a str,
+        port: u16,
+        uuid: String,
+        password: &'a str,
+        congestion_controller: Option<&'static str>,
+        udp_relay_mode: Option<&'static str>,
+        sni: Option<&'a str>,
+        alpn: Option<&'a [String]>,
+        skip_cert_verify: Option<bool>,
+    },
+
+    #[serde(rename = "socks5", rename_all = "kebab-case")]
+    Socks5 {
+        name: String,
+        server: &'a str,
+        port: u16,
+        username: Option<&'a str>,
+        password: Option<&'a str>,
+        tls: Option<bool>,
+        skip_cert_verify: Option<bool>,
+        udp: Option<bool>,
+    },
+
+    #[serde(rename = "http", rename_all = "kebab-case")]
+    Http {
+        name: String,
+        server: &'a str,
+        port: u16,
+        username: Option<&'a str>,
+        password: Option<&'a str>,
+        tls: Option<bool>,
+        skip_cert_verify: Option<bool>,
+        sni: Option<&'a str>,
+    },
+
+    #[serde(rename = "snell", rename_all = "kebab-case")]
+    Snell {
+        name: String,
+        server: &'a str,
+        port: u16,
+        psk: &'a str,
+        version: Option<u8>,
+        obfs_opts: Option<ClashMetaSsPluginObfsOpts>,
+    },
 }
 
 #[derive(Debug, Serialize)]
@@ -185,6 +285,70 @@ impl From<ClashMetaSsPluginObfsType> for ObfsType {
     }
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize)]
+#[serde(tag = "network", rename_all = "kebab-case")]
+pub enum ClashMetaTransport<'a> {
+    #[serde(rename_all = "kebab-case")]
+    Ws { ws_opts: ClashMetaWsOpts<'a> },
+    #[serde(rename_all = "kebab-case")]
+    H2 { h2_opts: ClashMetaH2Opts<'a> },
+    #[serde(rename_all = "kebab-case")]
+    Grpc { grpc_opts: ClashMetaGrpcOpts<'a> },
+}
+impl<'a> From<&'a Transport> for ClashMetaTransport<'a> {
+    fn from(value: &'a Transport) -> Self {
+        match value {
+            Transport::Ws { path, host } => Self::Ws {
+                ws_opts: ClashMetaWsOpts {
+                    path: path.as_deref(),
+                    headers: host.as_deref().map(|host| BTreeMap::from([("Host", host)])),
+                },
+            },
+            Transport::H2 { path, host } => Self::H2 {
+                h2_opts: ClashMetaH2Opts {
+                    host: host.as_deref(),
+                    path: path.as_deref(),
+                },
+            },
+            Transport::Grpc { service_name } => Self::Grpc {
+                grpc_opts: ClashMetaGrpcOpts {
+                    grpc_service_name: service_name.as_deref(),
+                },
+            },
+        }
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize)]
+pub struct ClashMetaWsOpts<'a> {
+    pub path: Option<&'a str>,
+    pub headers: Option<BTreeMap<&'static str, &'a str>>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize)]
+pub struct ClashMetaH2Opts<'a> {
+    pub host: Option<&'a [String]>,
+    pub path: Option<&'a str>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ClashMetaGrpcOpts<'a> {
+    pub grpc_service_name: Option<&'a str>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ClashMetaRealityOpts<'a> {
+    pub public_key: &'a str,
+    pub short_id: Option<&'a str>,
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "kebab-case")]
@@ -310,6 +474,125 @@ impl Adaptor for ClashMeta {
                 udp: None,
                 persistent_keepalive: None,
             }),
+
+            Node::Vmess(vmess_node) => Some(ClashMetaProxy::Vmess {
+                name: vmess_node.get_display_name(),
+                server: &vmess_node.server,
+                port: vmess_node.port,
+                uuid: vmess_node.uuid.to_string(),
+                alter_id: vmess_node.alter_id.unwrap_or(0),
+                cipher: vmess_node
+                    .security
+                    .map_or("auto", |security| security.get_alias()),
+                udp: None,
+                tls: vmess_node.tls.as_ref().map(|_| true),
+                skip_cert_verify: vmess_node.tls.as_ref().and_then(|tls| tls.insecure),
+                servername: vmess_node.tls.as_ref().and_then(|tls| tls.sni.as_deref()),
+                alpn: vmess_node.tls.as_ref().and_then(|tls| tls.alpn.as_deref()),
+                transport: vmess_node.transport.as_ref().map(Into::into),
+            }),
+
+            Node::Vless(vless_node) => Some(ClashMetaProxy::Vless {
+                name: vless_node.get_display_name(),
+                server: &vless_node.server,
+                port: vless_node.port,
+                uuid: vless_node.uuid.to_string(),
+                flow: vless_node.flow.as_deref(),
+                udp: None,
+                tls: vless_node.is_tls_enabled().then_some(true),
+                servername: vless_node.tls.as_ref().and_then(|tls| tls.sni.as_deref()),
+                skip_cert_verify: vless_node.tls.as_ref().and_then(|tls| tls.insecure),
+                alpn: vless_node.tls.as_ref().and_then(|tls| tls.alpn.as_deref()),
+                client_fingerprint: vless_node.fingerprint.as_deref(),
+                reality_opts: vless_node
+                    .reality
+                    .as_ref()
+                    .map(|reality| ClashMetaRealityOpts {
+                        public_key: &reality.public_key,
+                        short_id: reality.short_id.as_deref(),
+                    }),
+                transport: vless_node.transport.as_ref().map(Into::into),
+            }),
+
+            Node::Trojan(trojan_node) => {
+                // Clash.Meta only supports WebSocket and gRPC as the transport of Trojan.
+                if matches!(trojan_node.transport, Some(Transport::H2 { .. })) {
+                    return None;
+                }
+
+                Some(ClashMetaProxy::Trojan {
+                    name: trojan_node.get_display_name(),
+                    server: &trojan_node.server,
+                    port: trojan_node.port,
+                    password: &trojan_node.password,
+                    udp: None,
+                    sni: trojan_node.tls.sni.as_deref(),
+                    alpn: trojan_node.tls.alpn.as_deref(),
+                    skip_cert_verify: trojan_node.tls.insecure,
+                    transport: trojan_node.transport.as_ref().map(Into::into),
+                })
+            }
+
+            Node::Tuic(tuic_node) => Some(ClashMetaProxy::Tuic {
+                name: tuic_node.get_display_name(),
+                server: &tuic_node.server,
+                port: tuic_node.port,
+                uuid: tuic_node.uuid.to_string(),
+                password: &tuic_node.password,
+                congestion_controller: tuic_node
+                    .congestion_control
+                    .map(|congestion_control| congestion_control.get_alias()),
+                udp_relay_mode: tuic_node
+                    .udp_relay_mode
+                    .map(|udp_relay_mode| udp_relay_mode.get_alias()),
+                sni: tuic_node.tls.sni.as_deref(),
+                alpn: tuic_node.tls.alpn.as_deref(),
+                skip_cert_verify: tuic_node.tls.insecure,
+            }),
+
+            Node::Socks5(socks5_node) => Some(ClashMetaProxy::Socks5 {
+                name: socks5_node.get_display_name(),
+                server: &socks5_node.server,
+                port: socks5_node.port,
+                username: socks5_node.username.as_deref(),
+                password: socks5_node.password.as_deref(),
+                tls: socks5_node.tls.as_ref().map(|_| true),
+                skip_cert_verify: socks5_node.tls.as_ref().and_then(|tls| tls.insecure),
+                udp: None,
+            }),
+
+            Node::Http(http_node) => Some(ClashMetaProxy::Http {
+                name: http_node.get_display_name(),
+                server: &http_node.server,
+                port: http_node.port,
+                username: http_node.username.as_deref(),
+                password: http_node.password.as_deref(),
+                tls: http_node.tls.as_ref().map(|_| true),
+                skip_cert_verify: http_node.tls.as_ref().and_then(|tls| tls.insecure),
+                sni: http_node.tls.as_ref().and_then(|tls| tls.sni.as_deref()),
+            }),
+
+            Node::Snell(snell_node) => {
+                // Clash.Meta only supports Snell v1 to v3.
+                if matches!(snell_node.version, Some(version) if version > 3) {
+                    return None;
+                }
+
+                Some(ClashMetaProxy::Snell {
+                    name: snell_node.get_display_name(),
+                    server: &snell_node.server,
+                    port: snell_node.port,
+                    psk: &snell_node.psk,
+                    version: snell_node.version,
+                    obfs_opts: snell_node
+                        .obfs
+                        .as_ref()
+                        .map(|obfs| ClashMetaSsPluginObfsOpts {
+                            mode: Some(obfs.mode.into()),
+                            host: obfs.host.clone(),
+                        }),
+                })
+            }
         }
     }
 
@@ -322,3 +605,46 @@ impl Adaptor for ClashMeta {
         to_string(&nodes).unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::node::vless::RealityOptions;
+    use crate::node::VlessNode;
+    use crate::template::adaptors::ConvertNodesToString;
+
+    #[test]
+    fn reality_implies_tls() {
+        let node = Node::Vless(Box::new(VlessNode {
+            remarks: Some(String::from("vless-reality")),
+            server: String::from("example.com"),
+            port: 443,
+            uuid: Uuid::parse_str("b831381d-6324-4d53-ad4f-8cda48b30811").unwrap(),
+            flow: Some(String::from("xtls-rprx-vision")),
+            transport: None,
+            tls: None,
+            fingerprint: Some(String::from("chrome")),
+            reality: Some(RealityOptions {
+                public_key: String::from("public_key"),
+                short_id: None,
+            }),
+        }));
+
+        assert_eq!(
+            ClashMeta.nodes_to_string([node].iter(), Default::default()),
+            r#"- type: vless
+  name: vless-reality
+  server: example.com
+  port: 443
+  uuid: b831381d-6324-4d53-ad4f-8cda48b30811
+  flow: xtls-rprx-vision
+  tls: true
+  client-fingerprint: chrome
+  reality-opts:
+    public-key: public_key
+"#
+        );
+    }
+}
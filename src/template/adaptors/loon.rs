@@ -0,0 +1,348 @@
+use std::fmt::Display;
+
+use crate::node::hysteria2::Obfuscation as Hysteria2Obfuscation;
+use crate::node::ss::{Method as SsMethod, ObfsOpts, Plugin as SsPlugin};
+use crate::node::wireguard::WireguardNode;
+use crate::node::{GetNodeName, Node};
+
+use super::Adaptor;
+
+/// Loon Proxy
+/// Reference: https://nsloon.app/docs/Node/
+pub struct LoonProxy<'a> {
+    name: String,
+    proxy: ProxyType<'a>,
+}
+
+pub enum ProxyType<'a> {
+    Ss {
+        host: &'a str,
+        port: u16,
+        method: &'static str,
+        password: &'a str,
+        obfs: Option<&'a ObfsOpts>,
+        udp: bool,
+    },
+
+    Ssr {
+        host: &'a str,
+        port: u16,
+        method: &'a str,
+        password: &'a str,
+        protocol: &'a str,
+        protocol_param: Option<&'a str>,
+        obfs: &'a str,
+        obfs_param: Option<&'a str>,
+    },
+
+    Hysteria2 {
+        host: &'a str,
+        port: u16,
+        password: &'a str,
+        sni: Option<&'a str>,
+        skip_cert_verify: Option<bool>,
+        salamander_password: Option<&'a str>,
+        download_bandwidth: Option<u32>,
+    },
+
+    /// Unlike Surge, WireGuard is written inline in Loon.
+    Wireguard(&'a WireguardNode),
+}
+impl Display for ProxyType<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ss {
+                host,
+                port,
+                method,
+                password,
+                obfs,
+                udp,
+            } => {
+                write!(f, "Shadowsocks,{host},{port},{method},\"{password}\"")?;
+
+                if let Some(obfs) = obfs {
+                    if let Some(obfs_obfs) = obfs.obfs {
+                        write!(f, ",obfs-name={obfs_obfs}")?;
+                    }
+
+                    if let Some(host) = &obfs.host {
+                        write!(f, ",obfs-host={host}")?;
+                    }
+
+                    if let Some(uri) = &obfs.uri {
+                        write!(f, ",obfs-uri={uri}")?;
+                    }
+                }
+
+                write!(f, ",udp={udp}")?;
+            }
+
+            Self::Ssr {
+                host,
+                port,
+                method,
+                password,
+                protocol,
+                protocol_param,
+                obfs,
+                obfs_param,
+            } => {
+                write!(
+                    f,
+                    "ShadowsocksR,{host},{port},{method},\"{password}\",protocol={protocol}"
+                )?;
+
+                if let Some(protocol_param) = protocol_param {
+                    write!(f, ",protocol-param={protocol_param}")?;
+                }
+
+                write!(f, ",obfs={obfs}")?;
+
+                if let Some(obfs_param) = obfs_param {
+                    write!(f, ",obfs-param={obfs_param}")?;
+                }
+            }
+
+            Self::Hysteria2 {
+                host,
+                port,
+                password,
+                sni,
+                skip_cert_verify,
+                salamander_password,
+                download_bandwidth,
+            } => {
+                write!(f, "Hysteria2,{host},{port},\"{password}\"")?;
+
+                if let Some(sni) = sni {
+                    write!(f, ",sni={sni}")?;
+                }
+
+                if let Some(skip_cert_verify) = skip_cert_verify {
+                    write!(f, ",skip-cert-verify={skip_cert_verify}")?;
+                }
+
+                if let Some(salamander_password) = salamander_password {
+                    write!(f, ",salamander-password={salamander_password}")?;
+                }
+
+                if let Some(download_bandwidth) = download_bandwidth {
+                    write!(f, ",download-bandwidth={download_bandwidth}")?;
+                }
+
+                write!(f, ",udp=true")?;
+            }
+
+            Self::Wireguard(wireguard_node) => {
+                write!(f, "WireGuard")?;
+
+                if let Some(ip) = wireguard_node.ip {
+                    write!(f, ",interface-ip={ip}")?;
+                }
+
+                if let Some(ipv6) = wireguard_node.ipv6 {
+                    write!(f, ",interface-ipV6={ipv6}")?;
+                }
+
+                write!(f, ",private-key=\"{}\"", wireguard_node.private_key)?;
+
+                write!(
+                    f,
+                    ",peers=[{{public-key=\"{}\",allowed-ips=\"{}\"",
+                    wireguard_node.public_key,
+                    wireguard_node.get_allowed_ips().join(",")
+                )?;
+
+                if wireguard_node.server.contains(':') {
+                    write!(
+                        f,
+                        ",endpoint=[{}]:{}",
+                        wireguard_node.server, wireguard_node.port
+                    )?;
+                } else {
+                    write!(
+                        f,
+                        ",endpoint={}:{}",
+                        wireguard_node.server, wireguard_node.port
+                    )?;
+                }
+
+                if let Some(pre_shared_key) = &wireguard_node.pre_shared_key {
+                    write!(f, ",preshared-key=\"{pre_shared_key}\"")?;
+                }
+
+                if let Some([a, b, c]) = wireguard_node.reserved {
+                    write!(f, ",reserved=[{a},{b},{c}]")?;
+                }
+
+                write!(f, "}}]")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct Loon;
+impl Adaptor for Loon {
+    const ADAPTOR_NAME: &'static str = "loon";
+
+    type Node<'a> = LoonProxy<'a>;
+
+    fn convert_node<'a>(&self, node: &'a Node) -> Option<Self::Node<'a>> {
+        match node {
+            Node::Ss(ss_node) => {
+                let method = match ss_node.method {
+                    // Loon only supports the AES ciphers of Shadowsocks 2022.
+                    SsMethod::Ss2022Blake3Chacha20Poly1305
+                    | SsMethod::Ss2022Blake3Chacha8Poly1305 => return None,
+                    SsMethod::AeadChacha20Poly1305 => "chacha20-ietf-poly1305",
+                    method => method.get_alias(),
+                };
+
+                let obfs = if let Some(plugin) = &ss_node.plugin {
+                    if let SsPlugin::SimpleObfs(obfs_opts) = plugin {
+                        Some(obfs_opts)
+                    } else {
+                        return None;
+                    }
+                } else {
+                    None
+                };
+
+                Some(LoonProxy {
+                    name: ss_node.get_display_name(),
+                    proxy: ProxyType::Ss {
+                        host: &ss_node.server,
+                        port: ss_node.server_port,
+                        method,
+                        password: &ss_node.password,
+                        obfs,
+                        // Loon relays UDP natively and can't tunnel it over TCP,
+                        // so UDP is disabled for servers expecting UDP over TCP.
+                        udp: matches!(&ss_node.udp, Some(true) if !matches!(ss_node.udp_over_tcp, Some(true))),
+                    },
+                })
+            }
+
+            Node::Ssr(ssr_node) => Some(LoonProxy {
+                name: ssr_node.get_display_name(),
+                proxy: ProxyType::Ssr {
+                    host: &ssr_node.server,
+                    port: ssr_node.server_port,
+                    method: &ssr_node.method,
+                    password: &ssr_node.password,
+                    protocol: &ssr_node.protocol,
+                    protocol_param: ssr_node.protocol_param.as_deref(),
+                    obfs: &ssr_node.obfs,
+                    obfs_param: ssr_node.obfs_param.as_deref(),
+                },
+            }),
+
+            Node::Hysteria(_) => None,
+
+            Node::Hysteria2(hysteria2_node) => Some(LoonProxy {
+                name: hysteria2_node.get_display_name(),
+                proxy: ProxyType::Hysteria2 {
+                    host: &hysteria2_node.server,
+                    port: hysteria2_node.get_port(),
+                    // Loon requires the password of Hysteria2 nodes.
+                    password: hysteria2_node.auth.as_deref()?,
+                    sni: hysteria2_node.tls.sni.as_deref(),
+                    skip_cert_verify: hysteria2_node.tls.insecure,
+                    salamander_password: hysteria2_node.obfs.as_ref().map(|obfs| match obfs {
+                        Hysteria2Obfuscation::Salamander { password } => password.as_str(),
+                    }),
+                    download_bandwidth: hysteria2_node
+                        .down
+                        .as_ref()
+                        .and_then(|down| down.to_mbps()),
+                },
+            }),
+
+            Node::Wireguard(wireguard_node) => Some(LoonProxy {
+                name: wireguard_node.get_display_name(),
+                proxy: ProxyType::Wireguard(wireguard_node),
+            }),
+
+            Node::Vmess(_) => None,
+
+            Node::Vless(_) => None,
+
+            Node::Trojan(_) => None,
+
+            Node::Tuic(_) => None,
+
+            Node::Socks5(_) => None,
+
+            Node::Http(_) => None,
+
+            Node::Snell(_) => None,
+        }
+    }
+
+    fn serialize_nodes<'a, T: Iterator<Item = Self::Node<'a>>>(
+        &self,
+        nodes: T,
+        _options: super::NodesSerializationOptions,
+    ) -> String {
+        nodes
+            .into_iter()
+            .map(|node| format!("{} = {}", node.name, node.proxy))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::hysteria2::ServerPort;
+    use crate::node::Hysteria2Node;
+    use crate::template::adaptors::ConvertNodesToString;
+
+    #[test]
+    fn convert_wireguard_node() {
+        let node = Node::Wireguard(WireguardNode {
+            remarks: Some(String::from("wg")),
+            server: String::from("2606:4700:d0::a29f:c001"),
+            port: 2408,
+            ip: Some("172.16.0.2".parse().unwrap()),
+            ipv6: Some("2606:4700:110:8a36::2".parse().unwrap()),
+            private_key: String::from("private_key"),
+            public_key: String::from("public_key"),
+            pre_shared_key: Some(String::from("pre_shared_key")),
+            reserved: Some([1, 2, 3]),
+            allowed_ips: Some(vec![
+                String::from("10.0.0.0/8"),
+                String::from("2606:4700::/32"),
+            ]),
+        });
+
+        assert_eq!(
+            Loon.nodes_to_string([node].iter(), Default::default()),
+            "wg = WireGuard,interface-ip=172.16.0.2,interface-ipV6=2606:4700:110:8a36::2,\
+             private-key=\"private_key\",peers=[{public-key=\"public_key\",\
+             allowed-ips=\"10.0.0.0/8,2606:4700::/32\",endpoint=[2606:4700:d0::a29f:c001]:2408,\
+             preshared-key=\"pre_shared_key\",reserved=[1,2,3]}]"
+        );
+    }
+
+    #[test]
+    fn skip_hysteria2_node_without_password() {
+        let node = Node::Hysteria2(Box::new(Hysteria2Node {
+            remarks: Some(String::from("hy2")),
+            server: String::from("1.2.3.4"),
+            port: ServerPort::Single(443),
+            auth: None,
+            obfs: None,
+            up: None,
+            down: None,
+            tls: Default::default(),
+        }));
+
+        assert_eq!(Loon.nodes_to_string([node].iter(), Default::default()), "");
+    }
+}
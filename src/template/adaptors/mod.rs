@@ -9,8 +9,13 @@ use crate::node::{GetNodeName, Node};
 
 pub mod clash;
 mod clash_meta;
-mod sing_box;
+mod loon;
+mod quantumult_x;
+mod shadowsocks_rust;
+pub mod sing_box;
+mod stash;
 mod surge;
+mod xray;
 
 #[derive(Debug)]
 pub struct NodesSerializationOptions {
@@ -100,6 +105,11 @@ pub enum Adaptors {
     ClashMeta(clash_meta::ClashMeta),
     SingBox(sing_box::SingBox),
     Surge(surge::Surge),
+    QuantumultX(quantumult_x::QuantumultX),
+    Loon(loon::Loon),
+    Stash(stash::Stash),
+    Xray(xray::Xray),
+    ShadowsocksRust(shadowsocks_rust::ShadowsocksRust),
 }
 impl Adaptors {
     /// Determine whether the adaptor supports the node.
@@ -109,6 +119,11 @@ impl Adaptors {
             Self::ClashMeta(adaptor) => adaptor.convert_node(node).is_some(),
             Self::SingBox(adaptor) => adaptor.convert_node(node).is_some(),
             Self::Surge(adaptor) => adaptor.convert_node(node).is_some(),
+            Self::QuantumultX(adaptor) => adaptor.convert_node(node).is_some(),
+            Self::Loon(adaptor) => adaptor.convert_node(node).is_some(),
+            Self::Stash(adaptor) => adaptor.convert_node(node).is_some(),
+            Self::Xray(adaptor) => adaptor.convert_node(node).is_some(),
+            Self::ShadowsocksRust(adaptor) => adaptor.convert_node(node).is_some(),
         }
     }
 }
@@ -122,6 +137,15 @@ pub fn get_adaptor_from_args(args: &HashMap<String, Value>) -> Result<Option<Ada
             }
             sing_box::SingBox::ADAPTOR_NAME => Ok(Some(Adaptors::SingBox(Default::default()))),
             surge::Surge::ADAPTOR_NAME => Ok(Some(Adaptors::Surge(Default::default()))),
+            quantumult_x::QuantumultX::ADAPTOR_NAME => {
+                Ok(Some(Adaptors::QuantumultX(Default::default())))
+            }
+            loon::Loon::ADAPTOR_NAME => Ok(Some(Adaptors::Loon(Default::default()))),
+            stash::Stash::ADAPTOR_NAME => Ok(Some(Adaptors::Stash(Default::default()))),
+            xray::Xray::ADAPTOR_NAME => Ok(Some(Adaptors::Xray(Default::default()))),
+            shadowsocks_rust::ShadowsocksRust::ADAPTOR_NAME => {
+                Ok(Some(Adaptors::ShadowsocksRust(Default::default())))
+            }
 
             _ => Err(anyhow!(
                 "Unknown adaptor name: `{}`",
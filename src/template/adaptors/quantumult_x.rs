@@ -0,0 +1,567 @@
+use std::fmt::Display;
+
+use crate::node::common::{TlsOptions, Transport};
+use crate::node::ss::{Method as SsMethod, ObfsOpts, ObfsType, Plugin as SsPlugin};
+use crate::node::vmess::Security as VmessSecurity;
+use crate::node::{GetNodeName, Node};
+
+use super::Adaptor;
+
+/// Quantumult X Server
+/// Reference: https://github.com/crossutility/Quantumult-X/blob/master/sample.conf
+pub struct QuantumultXServer<'a> {
+    tag: String,
+    server: ServerType<'a>,
+}
+
+pub enum ServerType<'a> {
+    Ss {
+        host: &'a str,
+        port: u16,
+        method: &'static str,
+        password: &'a str,
+        obfs: Option<&'a ObfsOpts>,
+        udp_relay: bool,
+    },
+
+    /// Quantumult X writes SSR nodes as `shadowsocks` with extra `ssr-protocol` fields.
+    Ssr {
+        host: &'a str,
+        port: u16,
+        method: &'a str,
+        password: &'a str,
+        protocol: &'a str,
+        protocol_param: Option<&'a str>,
+        obfs: &'a str,
+        obfs_param: Option<&'a str>,
+    },
+
+    Vmess {
+        host: &'a str,
+        port: u16,
+        method: &'static str,
+        uuid: String,
+        ws: Option<(Option<&'a str>, Option<&'a str>)>,
+        tls: Option<&'a TlsOptions>,
+        aead: bool,
+    },
+
+    Trojan {
+        host: &'a str,
+        port: u16,
+        password: &'a str,
+        ws: Option<(Option<&'a str>, Option<&'a str>)>,
+        tls: &'a TlsOptions,
+    },
+
+    Socks5 {
+        host: &'a str,
+        port: u16,
+        username: Option<&'a str>,
+        password: Option<&'a str>,
+        tls: Option<&'a TlsOptions>,
+    },
+
+    Http {
+        host: &'a str,
+        port: u16,
+        username: Option<&'a str>,
+        password: Option<&'a str>,
+        tls: Option<&'a TlsOptions>,
+    },
+}
+impl ServerType<'_> {
+    fn write_address(f: &mut std::fmt::Formatter<'_>, host: &str, port: u16) -> std::fmt::Result {
+        if host.contains(':') && !host.starts_with('[') {
+            write!(f, "[{host}]:{port}")
+        } else {
+            write!(f, "{host}:{port}")
+        }
+    }
+
+    /// Write the `obfs` fields of WebSocket,
+    /// which become `wss` when TLS is enabled.
+    fn write_ws(
+        f: &mut std::fmt::Formatter<'_>,
+        ws_path: Option<&str>,
+        ws_host: Option<&str>,
+        tls: bool,
+    ) -> std::fmt::Result {
+        write!(f, ", obfs={}", if tls { "wss" } else { "ws" })?;
+
+        if let Some(ws_host) = ws_host {
+            write!(f, ", obfs-host={ws_host}")?;
+        }
+
+        if let Some(ws_path) = ws_path {
+            write!(f, ", obfs-uri={ws_path}")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_tls(f: &mut std::fmt::Formatter<'_>, tls: &TlsOptions) -> std::fmt::Result {
+        if let Some(sni) = &tls.sni {
+            write!(f, ", tls-host={sni}")?;
+        }
+
+        if let Some(insecure) = tls.insecure {
+            write!(f, ", tls-verification={}", !insecure)?;
+        }
+
+        Ok(())
+    }
+}
+impl Display for ServerType<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ss {
+                host,
+                port,
+                method,
+                password,
+                obfs,
+                udp_relay,
+            } => {
+                write!(f, "shadowsocks=")?;
+                Self::write_address(f, host, *port)?;
+                write!(f, ", method={method}, password={password}")?;
+
+                if let Some(obfs) = obfs {
+                    if let Some(obfs_obfs) = obfs.obfs {
+                        write!(f, ", obfs={obfs_obfs}")?;
+                    }
+
+                    if let Some(host) = &obfs.host {
+                        write!(f, ", obfs-host={host}")?;
+                    }
+
+                    // `obfs-uri` only works with `obfs=http`.
+                    if let (Some(ObfsType::Http), Some(uri)) = (obfs.obfs, &obfs.uri) {
+                        write!(f, ", obfs-uri={uri}")?;
+                    }
+                }
+
+                write!(f, ", udp-relay={udp_relay}")?;
+            }
+
+            Self::Ssr {
+                host,
+                port,
+                method,
+                password,
+                protocol,
+                protocol_param,
+                obfs,
+                obfs_param,
+            } => {
+                write!(f, "shadowsocks=")?;
+                Self::write_address(f, host, *port)?;
+                write!(
+                    f,
+                    ", method={method}, password={password}, ssr-protocol={protocol}"
+                )?;
+
+                if let Some(protocol_param) = protocol_param {
+                    write!(f, ", ssr-protocol-param={protocol_param}")?;
+                }
+
+                write!(f, ", obfs={obfs}")?;
+
+                if let Some(obfs_param) = obfs_param {
+                    write!(f, ", obfs-host={obfs_param}")?;
+                }
+            }
+
+            Self::Vmess {
+                host,
+                port,
+                method,
+                uuid,
+                ws,
+                tls,
+                aead,
+            } => {
+                write!(f, "vmess=")?;
+                Self::write_address(f, host, *port)?;
+                write!(f, ", method={method}, password={uuid}")?;
+
+                match (ws, tls) {
+                    (Some((ws_path, ws_host)), tls) => {
+                        Self::write_ws(f, *ws_path, *ws_host, tls.is_some())?
+                    }
+                    (None, Some(_)) => write!(f, ", obfs=over-tls")?,
+                    (None, None) => {}
+                }
+
+                if let Some(tls) = tls {
+                    Self::write_tls(f, tls)?;
+                }
+
+                write!(f, ", aead={aead}")?;
+            }
+
+            Self::Trojan {
+                host,
+                port,
+                password,
+                ws,
+                tls,
+            } => {
+                write!(f, "trojan=")?;
+                Self::write_address(f, host, *port)?;
+                write!(f, ", password={password}")?;
+
+                if let Some((ws_path, ws_host)) = ws {
+                    Self::write_ws(f, *ws_path, *ws_host, true)?;
+                } else {
+                    write!(f, ", over-tls=true")?;
+                }
+
+                Self::write_tls(f, tls)?;
+            }
+
+            Self::Socks5 {
+                host,
+                port,
+                username,
+                password,
+                tls,
+            }
+            | Self::Http {
+                host,
+                port,
+                username,
+                password,
+                tls,
+            } => {
+                let server_type = match self {
+                    Self::Socks5 { .. } => "socks5",
+                    _ => "http",
+                };
+                write!(f, "{server_type}=")?;
+                Self::write_address(f, host, *port)?;
+
+                if let Some(username) = username {
+                    write!(f, ", username={username}")?;
+                }
+
+                if let Some(password) = password {
+                    write!(f, ", password={password}")?;
+                }
+
+                if let Some(tls) = tls {
+                    write!(f, ", over-tls=true")?;
+                    Self::write_tls(f, tls)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Quantumult X splits the fields of a server on commas and doesn't support quoting,
+/// so nodes whose credentials contain commas can't be written.
+fn contains_comma<'a>(values: impl IntoIterator<Item = &'a str>) -> bool {
+    values.into_iter().any(|value| value.contains(','))
+}
+
+#[derive(Default)]
+pub struct QuantumultX;
+impl Adaptor for QuantumultX {
+    const ADAPTOR_NAME: &'static str = "quantumult-x";
+
+    type Node<'a> = QuantumultXServer<'a>;
+
+    fn convert_node<'a>(&self, node: &'a Node) -> Option<Self::Node<'a>> {
+        match node {
+            Node::Ss(ss_node) => {
+                if contains_comma([ss_node.password.as_str()]) {
+                    return None;
+                }
+
+                let method = match ss_node.method {
+                    // Quantumult X only supports the AES ciphers of Shadowsocks 2022.
+                    SsMethod::Ss2022Blake3Chacha20Poly1305
+                    | SsMethod::Ss2022Blake3Chacha8Poly1305 => return None,
+                    SsMethod::AeadChacha20Poly1305 => "chacha20-ietf-poly1305",
+                    method => method.get_alias(),
+                };
+
+                let obfs = if let Some(plugin) = &ss_node.plugin {
+                    if let SsPlugin::SimpleObfs(obfs_opts) = plugin {
+                        Some(obfs_opts)
+                    } else {
+                        return None;
+                    }
+                } else {
+                    None
+                };
+
+                Some(QuantumultXServer {
+                    tag: ss_node.get_display_name(),
+                    server: ServerType::Ss {
+                        host: &ss_node.server,
+                        port: ss_node.server_port,
+                        method,
+                        password: &ss_node.password,
+                        obfs,
+                        // `udp-relay` sends UDP packets as they are, which servers
+                        // expecting UDP over TCP can't handle, so it's disabled for them.
+                        udp_relay: matches!(&ss_node.udp, Some(true) if !matches!(ss_node.udp_over_tcp, Some(true))),
+                    },
+                })
+            }
+
+            Node::Ssr(ssr_node) => {
+                if contains_comma([ssr_node.password.as_str()]) {
+                    return None;
+                }
+
+                Some(QuantumultXServer {
+                    tag: ssr_node.get_display_name(),
+                    server: ServerType::Ssr {
+                        host: &ssr_node.server,
+                        port: ssr_node.server_port,
+                        method: &ssr_node.method,
+                        password: &ssr_node.password,
+                        protocol: &ssr_node.protocol,
+                        protocol_param: ssr_node.protocol_param.as_deref(),
+                        obfs: &ssr_node.obfs,
+                        obfs_param: ssr_node.obfs_param.as_deref(),
+                    },
+                })
+            }
+
+            Node::Hysteria(_) => None,
+
+            Node::Hysteria2(_) => None,
+
+            Node::Wireguard(_) => None,
+
+            Node::Vmess(vmess_node) => {
+                let method = match vmess_node.security {
+                    None | Some(VmessSecurity::Auto) | Some(VmessSecurity::Chacha20Poly1305) => {
+                        "chacha20-ietf-poly1305"
+                    }
+                    Some(VmessSecurity::Aes128Gcm) => "aes-128-gcm",
+                    Some(VmessSecurity::None) => "none",
+                    Some(VmessSecurity::Zero) => return None,
+                };
+
+                // Quantumult X only supports WebSocket as the transport of VMess.
+                let ws = match &vmess_node.transport {
+                    Some(Transport::Ws { path, host }) => Some((path.as_deref(), host.as_deref())),
+                    Some(_) => return None,
+                    None => None,
+                };
+
+                Some(QuantumultXServer {
+                    tag: vmess_node.get_display_name(),
+                    server: ServerType::Vmess {
+                        host: &vmess_node.server,
+                        port: vmess_node.port,
+                        method,
+                        uuid: vmess_node.uuid.to_string(),
+                        ws,
+                        tls: vmess_node.tls.as_ref(),
+                        aead: matches!(vmess_node.alter_id, None | Some(0)),
+                    },
+                })
+            }
+
+            Node::Vless(_) => None,
+
+            Node::Trojan(trojan_node) => {
+                if contains_comma([trojan_node.password.as_str()]) {
+                    return None;
+                }
+
+                // Quantumult X only supports WebSocket as the transport of Trojan.
+                let ws = match &trojan_node.transport {
+                    Some(Transport::Ws { path, host }) => Some((path.as_deref(), host.as_deref())),
+                    Some(_) => return None,
+                    None => None,
+                };
+
+                Some(QuantumultXServer {
+                    tag: trojan_node.get_display_name(),
+                    server: ServerType::Trojan {
+                        host: &trojan_node.server,
+                        port: trojan_node.port,
+                        password: &trojan_node.password,
+                        ws,
+                        tls: &trojan_node.tls,
+                    },
+                })
+            }
+
+            Node::Tuic(_) => None,
+
+            Node::Socks5(socks5_node) => {
+                let username = socks5_node.username.as_deref();
+                let password = socks5_node.password.as_deref();
+                if contains_comma(username.into_iter().chain(password)) {
+                    return None;
+                }
+
+                Some(QuantumultXServer {
+                    tag: socks5_node.get_display_name(),
+                    server: ServerType::Socks5 {
+                        host: &socks5_node.server,
+                        port: socks5_node.port,
+                        username,
+                        password,
+                        tls: socks5_node.tls.as_ref(),
+                    },
+                })
+            }
+
+            Node::Http(http_node) => {
+                let username = http_node.username.as_deref();
+                let password = http_node.password.as_deref();
+                if contains_comma(username.into_iter().chain(password)) {
+                    return None;
+                }
+
+                Some(QuantumultXServer {
+                    tag: http_node.get_display_name(),
+                    server: ServerType::Http {
+                        host: &http_node.server,
+                        port: http_node.port,
+                        username,
+                        password,
+                        tls: http_node.tls.as_ref(),
+                    },
+                })
+            }
+
+            Node::Snell(_) => None,
+        }
+    }
+
+    fn serialize_nodes<'a, T: Iterator<Item = Self::Node<'a>>>(
+        &self,
+        nodes: T,
+        _options: super::NodesSerializationOptions,
+    ) -> String {
+        nodes
+            .into_iter()
+            .map(|node| format!("{}, tag={}", node.server, node.tag))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::node::{Socks5Node, SsNode, SsrNode, TrojanNode, VmessNode};
+    use crate::template::adaptors::ConvertNodesToString;
+
+    #[test]
+    fn convert_nodes() {
+        let nodes = [
+            Node::Ss(Box::new(SsNode {
+                id: None,
+                remarks: Some(String::from("ss-obfs")),
+                server: String::from("1.2.3.4"),
+                server_port: 8388,
+                password: String::from("password"),
+                method: SsMethod::AeadChacha20Poly1305,
+                udp: Some(true),
+                udp_over_tcp: None,
+                plugin: Some(SsPlugin::SimpleObfs(ObfsOpts {
+                    obfs: Some(ObfsType::Http),
+                    host: Some(String::from("example.com")),
+                    uri: Some(String::from("/obfs")),
+                })),
+            })),
+            Node::Ssr(Box::new(SsrNode {
+                remarks: Some(String::from("ssr")),
+                server: String::from("1.2.3.4"),
+                server_port: 8389,
+                password: String::from("password"),
+                method: String::from("aes-256-cfb"),
+                protocol: String::from("auth_aes128_md5"),
+                protocol_param: Some(String::from("1:param")),
+                obfs: String::from("tls1.2_ticket_auth"),
+                obfs_param: Some(String::from("example.com")),
+                udpport: None,
+                uot: None,
+            })),
+            Node::Vmess(Box::new(VmessNode {
+                remarks: Some(String::from("vmess-ws-tls")),
+                server: String::from("example.com"),
+                port: 443,
+                uuid: Uuid::parse_str("b831381d-6324-4d53-ad4f-8cda48b30811").unwrap(),
+                alter_id: Some(0),
+                security: Some(VmessSecurity::Aes128Gcm),
+                transport: Some(Transport::Ws {
+                    path: Some(String::from("/ws")),
+                    host: Some(String::from("cdn.example.com")),
+                }),
+                tls: Some(TlsOptions {
+                    sni: Some(String::from("cdn.example.com")),
+                    insecure: Some(false),
+                    alpn: None,
+                }),
+            })),
+            Node::Trojan(Box::new(TrojanNode {
+                remarks: Some(String::from("trojan")),
+                server: String::from("2001:db8::1"),
+                port: 443,
+                password: String::from("password"),
+                transport: None,
+                tls: TlsOptions {
+                    sni: Some(String::from("example.com")),
+                    insecure: Some(true),
+                    alpn: None,
+                },
+            })),
+            Node::Socks5(Box::new(Socks5Node {
+                remarks: Some(String::from("socks5")),
+                server: String::from("1.2.3.4"),
+                port: 1080,
+                username: Some(String::from("user")),
+                password: None,
+                tls: None,
+            })),
+        ];
+
+        assert_eq!(
+            QuantumultX.nodes_to_string(nodes.iter(), Default::default()),
+            "shadowsocks=1.2.3.4:8388, method=chacha20-ietf-poly1305, password=password, \
+             obfs=http, obfs-host=example.com, obfs-uri=/obfs, udp-relay=true, tag=ss-obfs
+shadowsocks=1.2.3.4:8389, method=aes-256-cfb, password=password, \
+             ssr-protocol=auth_aes128_md5, ssr-protocol-param=1:param, \
+             obfs=tls1.2_ticket_auth, obfs-host=example.com, tag=ssr
+vmess=example.com:443, method=aes-128-gcm, password=b831381d-6324-4d53-ad4f-8cda48b30811, \
+             obfs=wss, obfs-host=cdn.example.com, obfs-uri=/ws, \
+             tls-host=cdn.example.com, tls-verification=true, aead=true, tag=vmess-ws-tls
+trojan=[2001:db8::1]:443, password=password, over-tls=true, \
+             tls-host=example.com, tls-verification=false, tag=trojan
+socks5=1.2.3.4:1080, username=user, tag=socks5"
+        );
+    }
+
+    #[test]
+    fn skip_nodes_with_comma_in_password() {
+        let node = Node::Trojan(Box::new(TrojanNode {
+            remarks: Some(String::from("trojan")),
+            server: String::from("example.com"),
+            port: 443,
+            password: String::from("pass,comma"),
+            transport: None,
+            tls: Default::default(),
+        }));
+
+        assert_eq!(
+            QuantumultX.nodes_to_string([node].iter(), Default::default()),
+            ""
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use serde::Serialize;
+use serde_json::to_string_pretty;
+use serde_with::skip_serializing_none;
+
+use crate::node::ss::{Method as SsMethod, Plugin as SsPlugin};
+use crate::node::{GetNodeName, Node};
+
+use super::Adaptor;
+
+/// shadowsocks-rust server
+/// Reference: https://github.com/shadowsocks/shadowsocks-rust#configuration
+#[skip_serializing_none]
+#[derive(Serialize)]
+pub struct ShadowsocksRustServer<'a> {
+    remarks: String,
+    server: &'a str,
+    server_port: u16,
+    method: &'static str,
+    password: &'a str,
+    plugin: Option<&'a str>,
+    plugin_opts: Option<String>,
+    mode: &'static str,
+    udp_over_tcp: Option<bool>,
+}
+
+#[derive(Default)]
+pub struct ShadowsocksRust;
+impl Adaptor for ShadowsocksRust {
+    const ADAPTOR_NAME: &'static str = "shadowsocks-rust";
+
+    type Node<'a> = ShadowsocksRustServer<'a>;
+
+    fn convert_node<'a>(&self, node: &'a Node) -> Option<Self::Node<'a>> {
+        match node {
+            Node::Ss(ss_node) => Some(ShadowsocksRustServer {
+                remarks: ss_node.get_display_name(),
+                // shadowsocks-rust expects IPv6 addresses without brackets.
+                server: ss_node
+                    .server
+                    .strip_prefix('[')
+                    .and_then(|server| server.strip_suffix(']'))
+                    .unwrap_or(&ss_node.server),
+                server_port: ss_node.server_port,
+                method: match ss_node.method {
+                    SsMethod::AeadChacha20Poly1305 => "chacha20-ietf-poly1305",
+                    method => method.get_alias(),
+                },
+                password: &ss_node.password,
+                plugin: match &ss_node.plugin {
+                    Some(SsPlugin::SimpleObfs(_)) => Some("obfs-local"),
+                    Some(SsPlugin::Unknown { plugin_name, .. }) => Some(plugin_name),
+                    None => None,
+
+                    // The options of other plugins are not parsed yet.
+                    _ => {
+                        return None;
+                    }
+                },
+                plugin_opts: ss_node
+                    .plugin
+                    .as_ref()
+                    .and_then(|plugin| plugin.get_opts_string()),
+                mode: if matches!(ss_node.udp, Some(true)) {
+                    "tcp_and_udp"
+                } else {
+                    "tcp_only"
+                },
+                // UDP packets are sent over the TCP connection instead of being relayed.
+                udp_over_tcp: (matches!(ss_node.udp, Some(true))
+                    && matches!(ss_node.udp_over_tcp, Some(true)))
+                .then_some(true),
+            }),
+
+            _ => None,
+        }
+    }
+
+    fn serialize_nodes<'a, T: Iterator<Item = Self::Node<'a>>>(
+        &self,
+        nodes: T,
+        options: super::NodesSerializationOptions,
+    ) -> String {
+        let nodes: Vec<_> = nodes.collect();
+
+        if nodes.is_empty() {
+            return String::from("");
+        }
+
+        let mut output = to_string_pretty(&nodes).unwrap();
+
+        if options.include_array_brackets {
+            output
+        } else {
+            output.pop();
+            output.pop();
+            output.split_off(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::SsNode;
+    use crate::template::adaptors::ConvertNodesToString;
+
+    #[test]
+    fn convert_udp_over_tcp_node() {
+        let node = Node::Ss(Box::new(SsNode {
+            id: None,
+            remarks: Some(String::from("ss-uot")),
+            server: String::from("[2001:db8::1]"),
+            server_port: 8388,
+            password: String::from("password"),
+            method: SsMethod::AeadAes256Gcm,
+            udp: Some(true),
+            udp_over_tcp: Some(true),
+            plugin: None,
+        }));
+
+        assert_eq!(
+            ShadowsocksRust.nodes_to_string([node].iter(), Default::default()),
+            r#"[
+  {
+    "remarks": "ss-uot",
+    "server": "2001:db8::1",
+    "server_port": 8388,
+    "method": "aes-256-gcm",
+    "password": "password",
+    "mode": "tcp_and_udp",
+    "udp_over_tcp": true
+  }
+]"#
+        );
+    }
+}
@@ -1,11 +1,13 @@
-use serde::Serialize;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
 use serde_json::to_string_pretty;
 use serde_with::skip_serializing_none;
 
 use crate::node::{
-    hysteria::ServerPort as HysteriaServerPort, hysteria::Speed as HysteriaSpeed,
-    hysteria2::Obfuscation as Hysteria2Obfuscation, hysteria2::ServerPort as Hysteria2ServerPort,
-    ss::Plugin as SsPlugin, GetNodeName, Node,
+    common::Transport, hysteria::ServerPort as HysteriaServerPort,
+    hysteria::Speed as HysteriaSpeed, hysteria2::Obfuscation as Hysteria2Obfuscation,
+    hysteria2::ServerPort as Hysteria2ServerPort, ss::Plugin as SsPlugin, GetNodeName, Node,
 };
 
 use super::Adaptor;
@@ -96,6 +98,83 @@ pub enum SingBoxNode<'a> {
         mtu: Option<u32>,
         network: Option<&'static str>,
     },
+
+    /// VMess outbound
+    /// Reference: https://sing-box.sagernet.org/configuration/outbound/vmess
+    #[serde(rename = "vmess")]
+    Vmess {
+        tag: String,
+        server: &'a str,
+        server_port: u16,
+        uuid: String,
+        security: Option<&'a str>,
+        alter_id: Option<u16>,
+        tls: Option<SingBoxTlsOptions<'a>>,
+        transport: Option<SingBoxTransport<'a>>,
+    },
+
+    /// VLESS outbound
+    /// Reference: https://sing-box.sagernet.org/configuration/outbound/vless
+    #[serde(rename = "vless")]
+    Vless {
+        tag: String,
+        server: &'a str,
+        server_port: u16,
+        uuid: String,
+        flow: Option<&'a str>,
+        tls: Option<SingBoxTlsOptions<'a>>,
+        transport: Option<SingBoxTransport<'a>>,
+    },
+
+    /// Trojan outbound
+    /// Reference: https://sing-box.sagernet.org/configuration/outbound/trojan
+    #[serde(rename = "trojan")]
+    Trojan {
+        tag: String,
+        server: &'a str,
+        server_port: u16,
+        password: &'a str,
+        tls: SingBoxTlsOptions<'a>,
+        transport: Option<SingBoxTransport<'a>>,
+    },
+
+    /// TUIC outbound
+    /// Reference: https://sing-box.sagernet.org/configuration/outbound/tuic
+    #[serde(rename = "tuic")]
+    Tuic {
+        tag: String,
+        server: &'a str,
+        server_port: u16,
+        uuid: String,
+        password: &'a str,
+        congestion_control: Option<&'static str>,
+        udp_relay_mode: Option<&'static str>,
+        tls: SingBoxTlsOptions<'a>,
+    },
+
+    /// SOCKS outbound
+    /// Reference: https://sing-box.sagernet.org/configuration/outbound/socks
+    #[serde(rename = "socks")]
+    Socks {
+        tag: String,
+        server: &'a str,
+        server_port: u16,
+        version: &'static str,
+        username: Option<&'a str>,
+        password: Option<&'a str>,
+    },
+
+    /// HTTP outbound
+    /// Reference: https://sing-box.sagernet.org/configuration/outbound/http
+    #[serde(rename = "http")]
+    Http {
+        tag: String,
+        server: &'a str,
+        server_port: u16,
+        username: Option<&'a str>,
+        password: Option<&'a str>,
+        tls: Option<SingBoxTlsOptions<'a>>,
+    },
 }
 
 /// TLS Options
@@ -107,11 +186,68 @@ pub struct SingBoxTlsOptions<'a> {
     server_name: Option<&'a str>,
     insecure: Option<bool>,
     alpn: Option<&'a [String]>,
+    utls: Option<SingBoxUtlsOptions<'a>>,
+    reality: Option<SingBoxRealityOptions<'a>>,
 }
 
-/// Singbox Hysteria2 Obfuscation
+/// uTLS Options
+/// Reference: https://sing-box.sagernet.org/configuration/shared/tls/#utls
+#[derive(Serialize)]
+pub struct SingBoxUtlsOptions<'a> {
+    enabled: bool,
+    fingerprint: &'a str,
+}
+
+/// REALITY Options
+/// Reference: https://sing-box.sagernet.org/configuration/shared/tls/#reality-fields
+#[skip_serializing_none]
+#[derive(Serialize)]
+pub struct SingBoxRealityOptions<'a> {
+    enabled: bool,
+    public_key: &'a str,
+    short_id: Option<&'a str>,
+}
+
+/// V2Ray Transport
+/// Reference: https://sing-box.sagernet.org/configuration/shared/v2ray-transport
+#[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(tag = "type")]
+pub enum SingBoxTransport<'a> {
+    #[serde(rename = "ws")]
+    Ws {
+        path: Option<&'a str>,
+        headers: Option<BTreeMap<&'static str, &'a str>>,
+    },
+    #[serde(rename = "http")]
+    Http {
+        host: Option<&'a [String]>,
+        path: Option<&'a str>,
+    },
+    #[serde(rename = "grpc")]
+    Grpc { service_name: Option<&'a str> },
+}
+impl<'a> From<&'a Transport> for SingBoxTransport<'a> {
+    fn from(value: &'a Transport) -> Self {
+        match value {
+            Transport::Ws { path, host } => Self::Ws {
+                path: path.as_deref(),
+                headers: host.as_deref().map(|host| BTreeMap::from([("Host", host)])),
+            },
+            Transport::H2 { path, host } => Self::Http {
+                host: host.as_deref(),
+                path: path.as_deref(),
+            },
+            Transport::Grpc { service_name } => Self::Grpc {
+                service_name: service_name.as_deref(),
+            },
+        }
+    }
+}
+
+/// Singbox Hysteria2 Obfuscation
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum SingBoxHysteria2Obfuscation {
     #[serde(rename = "salamander")]
     Salamander { password: String },
@@ -209,6 +345,8 @@ impl Adaptor for SingBox {
                     ),
                     insecure: hysteria_node.tls.insecure,
                     alpn: hysteria_node.tls.alpn.as_deref(),
+                    utls: None,
+                    reality: None,
                 },
             }),
 
@@ -244,6 +382,8 @@ impl Adaptor for SingBox {
                     ),
                     insecure: hysteria2_node.tls.insecure,
                     alpn: hysteria2_node.tls.alpn.as_deref(),
+                    utls: None,
+                    reality: None,
                 },
             }),
 
@@ -268,6 +408,135 @@ impl Adaptor for SingBox {
                 mtu: None,
                 network: None,
             }),
+
+            Node::Vmess(vmess_node) => Some(SingBoxNode::Vmess {
+                tag: vmess_node.get_display_name(),
+                server: &vmess_node.server,
+                server_port: vmess_node.port,
+                uuid: vmess_node.uuid.to_string(),
+                security: vmess_node.security.map(|security| security.get_alias()),
+                alter_id: vmess_node.alter_id,
+                tls: vmess_node.tls.as_ref().map(|tls| SingBoxTlsOptions {
+                    enabled: true,
+                    server_name: tls.sni.as_deref(),
+                    insecure: tls.insecure,
+                    alpn: tls.alpn.as_deref(),
+                    utls: None,
+                    reality: None,
+                }),
+                transport: vmess_node.transport.as_ref().map(Into::into),
+            }),
+
+            Node::Vless(vless_node) => Some(SingBoxNode::Vless {
+                tag: vless_node.get_display_name(),
+                server: &vless_node.server,
+                server_port: vless_node.port,
+                uuid: vless_node.uuid.to_string(),
+                flow: vless_node.flow.as_deref(),
+                tls: vless_node.is_tls_enabled().then(|| SingBoxTlsOptions {
+                    enabled: true,
+                    server_name: vless_node.tls.as_ref().and_then(|tls| tls.sni.as_deref()),
+                    insecure: vless_node.tls.as_ref().and_then(|tls| tls.insecure),
+                    alpn: vless_node.tls.as_ref().and_then(|tls| tls.alpn.as_deref()),
+                    // uTLS is required by REALITY in sing-box,
+                    // so fallback to `chrome` when the fingerprint is not specified.
+                    utls: vless_node
+                        .fingerprint
+                        .as_deref()
+                        .or(vless_node.reality.as_ref().map(|_| "chrome"))
+                        .map(|fingerprint| SingBoxUtlsOptions {
+                            enabled: true,
+                            fingerprint,
+                        }),
+                    reality: vless_node
+                        .reality
+                        .as_ref()
+                        .map(|reality| SingBoxRealityOptions {
+                            enabled: true,
+                            public_key: &reality.public_key,
+                            short_id: reality.short_id.as_deref(),
+                        }),
+                }),
+                transport: vless_node.transport.as_ref().map(Into::into),
+            }),
+
+            Node::Trojan(trojan_node) => Some(SingBoxNode::Trojan {
+                tag: trojan_node.get_display_name(),
+                server: &trojan_node.server,
+                server_port: trojan_node.port,
+                password: &trojan_node.password,
+                tls: SingBoxTlsOptions {
+                    enabled: true,
+                    server_name: Some(
+                        trojan_node
+                            .tls
+                            .sni
+                            .as_deref()
+                            .unwrap_or(&trojan_node.server),
+                    ),
+                    insecure: trojan_node.tls.insecure,
+                    alpn: trojan_node.tls.alpn.as_deref(),
+                    utls: None,
+                    reality: None,
+                },
+                transport: trojan_node.transport.as_ref().map(Into::into),
+            }),
+
+            Node::Tuic(tuic_node) => Some(SingBoxNode::Tuic {
+                tag: tuic_node.get_display_name(),
+                server: &tuic_node.server,
+                server_port: tuic_node.port,
+                uuid: tuic_node.uuid.to_string(),
+                password: &tuic_node.password,
+                congestion_control: tuic_node
+                    .congestion_control
+                    .map(|congestion_control| congestion_control.get_alias()),
+                udp_relay_mode: tuic_node
+                    .udp_relay_mode
+                    .map(|udp_relay_mode| udp_relay_mode.get_alias()),
+                tls: SingBoxTlsOptions {
+                    enabled: true,
+                    server_name: Some(tuic_node.tls.sni.as_deref().unwrap_or(&tuic_node.server)),
+                    insecure: tuic_node.tls.insecure,
+                    alpn: tuic_node.tls.alpn.as_deref(),
+                    utls: None,
+                    reality: None,
+                },
+            }),
+
+            Node::Socks5(socks5_node) => {
+                // SOCKS over TLS is not supported in sing-box.
+                if socks5_node.tls.is_some() {
+                    return None;
+                }
+
+                Some(SingBoxNode::Socks {
+                    tag: socks5_node.get_display_name(),
+                    server: &socks5_node.server,
+                    server_port: socks5_node.port,
+                    version: "5",
+                    username: socks5_node.username.as_deref(),
+                    password: socks5_node.password.as_deref(),
+                })
+            }
+
+            Node::Http(http_node) => Some(SingBoxNode::Http {
+                tag: http_node.get_display_name(),
+                server: &http_node.server,
+                server_port: http_node.port,
+                username: http_node.username.as_deref(),
+                password: http_node.password.as_deref(),
+                tls: http_node.tls.as_ref().map(|tls| SingBoxTlsOptions {
+                    enabled: true,
+                    server_name: tls.sni.as_deref(),
+                    insecure: tls.insecure,
+                    alpn: tls.alpn.as_deref(),
+                    utls: None,
+                    reality: None,
+                }),
+            }),
+
+            Node::Snell(_) => None,
         }
     }
 
@@ -293,3 +562,43 @@ impl Adaptor for SingBox {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::node::vless::RealityOptions;
+    use crate::node::VlessNode;
+    use crate::template::adaptors::ConvertNodesToString;
+
+    #[test]
+    fn reality_implies_tls() {
+        let node = Node::Vless(Box::new(VlessNode {
+            remarks: Some(String::from("vless-reality")),
+            server: String::from("example.com"),
+            port: 443,
+            uuid: Uuid::parse_str("b831381d-6324-4d53-ad4f-8cda48b30811").unwrap(),
+            flow: None,
+            transport: None,
+            tls: None,
+            fingerprint: None,
+            reality: Some(RealityOptions {
+                public_key: String::from("public_key"),
+                short_id: Some(String::from("0123")),
+            }),
+        }));
+
+        let outbound: serde_json::Value =
+            serde_json::from_str(&SingBox.nodes_to_string([node].iter(), Default::default()))
+                .unwrap();
+        assert_eq!(
+            outbound[0]["tls"],
+            serde_json::json!({
+                "enabled": true,
+                "utls": { "enabled": true, "fingerprint": "chrome" },
+                "reality": { "enabled": true, "public_key": "public_key", "short_id": "0123" }
+            })
+        );
+    }
+}
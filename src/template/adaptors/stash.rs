@@ -0,0 +1,403 @@
+use serde::Serialize;
+use serde_with::skip_serializing_none;
+use serde_yaml::to_string;
+
+use crate::node::common::Transport;
+use crate::node::hysteria2::Obfuscation as Hysteria2Obfuscation;
+use crate::node::{GetNodeName, Node};
+
+use super::clash_meta::{
+    ClashMetaRealityOpts, ClashMetaSsPlugin, ClashMetaSsPluginObfsOpts, ClashMetaTransport,
+};
+use super::Adaptor;
+
+/// Stash Proxy Configuration
+/// Stash is compatible with Clash, but some proxy types have their own field layouts.
+/// Reference: https://stash.wiki/proxy-protocols/proxy-types
+#[skip_serializing_none]
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub enum StashProxy<'a> {
+    #[serde(rename = "ss", rename_all = "kebab-case")]
+    Ss {
+        name: String,
+        server: &'a str,
+        port: u16,
+        cipher: &'a str,
+        password: &'a str,
+        udp: Option<bool>,
+        udp_over_tcp: Option<bool>,
+        #[serde(flatten)]
+        plugin: Option<ClashMetaSsPlugin>,
+    },
+
+    #[serde(rename = "ssr", rename_all = "kebab-case")]
+    Ssr {
+        name: String,
+        server: &'a str,
+        port: u16,
+        cipher: &'a str,
+        password: &'a str,
+        obfs: &'a str,
+        obfs_param: Option<&'a str>,
+        protocol: &'a str,
+        protocol_param: Option<&'a str>,
+    },
+
+    #[serde(rename = "hysteria", rename_all = "kebab-case")]
+    Hysteria {
+        name: String,
+        server: &'a str,
+        port: u16,
+        auth_str: Option<&'a str>,
+        obfs: Option<&'a str>,
+        protocol: Option<String>,
+        /// In Mbps.
+        up_speed: u32,
+        /// In Mbps.
+        down_speed: u32,
+        sni: Option<&'a str>,
+        alpn: Option<&'a [String]>,
+        skip_cert_verify: Option<bool>,
+    },
+
+    #[serde(rename = "hysteria2", rename_all = "kebab-case")]
+    Hysteria2 {
+        name: String,
+        server: &'a str,
+        port: u16,
+        auth: Option<&'a str>,
+        obfs: Option<&'a str>,
+        obfs_password: Option<&'a str>,
+        /// In Mbps.
+        up_speed: Option<u32>,
+        /// In Mbps.
+        down_speed: Option<u32>,
+        sni: Option<&'a str>,
+        alpn: Option<&'a [String]>,
+        skip_cert_verify: Option<bool>,
+    },
+
+    #[serde(rename = "wireguard", rename_all = "kebab-case")]
+    Wireguard {
+        name: String,
+        server: &'a str,
+        port: u16,
+        ip: Option<String>,
+        ipv6: Option<String>,
+        private_key: &'a str,
+        public_key: &'a str,
+        preshared_key: Option<&'a str>,
+        reserved: Option<[u8; 3]>,
+    },
+
+    #[serde(rename = "vmess", rename_all = "kebab-case")]
+    Vmess {
+        name: String,
+        server: &'a str,
+        port: u16,
+        uuid: String,
+        #[serde(rename = "alterId")]
+        alter_id: u16,
+        cipher: &'a str,
+        tls: Option<bool>,
+        skip_cert_verify: Option<bool>,
+        servername: Option<&'a str>,
+        alpn: Option<&'a [String]>,
+        #[serde(flatten)]
+        transport: Option<ClashMetaTransport<'a>>,
+    },
+
+    #[serde(rename = "vless", rename_all = "kebab-case")]
+    Vless {
+        name: String,
+        server: &'a str,
+        port: u16,
+        uuid: String,
+        flow: Option<&'a str>,
+        tls: Option<bool>,
+        servername: Option<&'a str>,
+        skip_cert_verify: Option<bool>,
+        alpn: Option<&'a [String]>,
+        client_fingerprint: Option<&'a str>,
+        reality_opts: Option<ClashMetaRealityOpts<'a>>,
+        #[serde(flatten)]
+        transport: Option<ClashMetaTransport<'a>>,
+    },
+
+    #[serde(rename = "trojan", rename_all = "kebab-case")]
+    Trojan {
+        name: String,
+        server: &'a str,
+        port: u16,
+        password: &'a str,
+        sni: Option<&'a str>,
+        alpn: Option<&'a [String]>,
+        skip_cert_verify: Option<bool>,
+        #[serde(flatten)]
+        transport: Option<ClashMetaTransport<'a>>,
+    },
+
+    #[serde(rename = "tuic", rename_all = "kebab-case")]
+    Tuic {
+        name: String,
+        server: &'a str,
+        port: u16,
+        version: u8,
+        uuid: String,
+        password: &'a str,
+        congestion_controller: Option<&'static str>,
+        udp_relay_mode: Option<&'static str>,
+        sni: Option<&'a str>,
+        alpn: Option<&'a [String]>,
+        skip_cert_verify: Option<bool>,
+    },
+
+    #[serde(rename = "socks5", rename_all = "kebab-case")]
+    Socks5 {
+        name: String,
+        server: &'a str,
+        port: u16,
+        username: Option<&'a str>,
+        password: Option<&'a str>,
+        tls: Option<bool>,
+        skip_cert_verify: Option<bool>,
+    },
+
+    #[serde(rename = "http", rename_all = "kebab-case")]
+    Http {
+        name: String,
+        server: &'a str,
+        port: u16,
+        username: Option<&'a str>,
+        password: Option<&'a str>,
+        tls: Option<bool>,
+        skip_cert_verify: Option<bool>,
+        sni: Option<&'a str>,
+    },
+
+    #[serde(rename = "snell", rename_all = "kebab-case")]
+    Snell {
+        name: String,
+        server: &'a str,
+        port: u16,
+        psk: &'a str,
+        version: Option<u8>,
+        obfs_opts: Option<ClashMetaSsPluginObfsOpts>,
+    },
+}
+
+#[derive(Default)]
+pub struct Stash;
+impl Adaptor for Stash {
+    const ADAPTOR_NAME: &'static str = "stash";
+
+    type Node<'a> = StashProxy<'a>;
+
+    fn convert_node<'a>(&self, node: &'a Node) -> Option<Self::Node<'a>> {
+        match node {
+            Node::Ss(ss_node) => {
+                let plugin = if let Some(plugin) = &ss_node.plugin {
+                    Some(plugin.clone().try_into().ok()?)
+                } else {
+                    None
+                };
+
+                Some(StashProxy::Ss {
+                    name: ss_node.get_display_name(),
+                    server: &ss_node.server,
+                    port: ss_node.server_port,
+                    cipher: ss_node.method.get_alias(),
+                    password: &ss_node.password,
+                    udp: ss_node.udp,
+                    udp_over_tcp: ss_node.udp_over_tcp,
+                    plugin,
+                })
+            }
+
+            Node::Ssr(ssr_node) => Some(StashProxy::Ssr {
+                name: ssr_node.get_display_name(),
+                server: &ssr_node.server,
+                port: ssr_node.server_port,
+                cipher: &ssr_node.method,
+                password: &ssr_node.password,
+                obfs: &ssr_node.obfs,
+                obfs_param: ssr_node.obfs_param.as_deref(),
+                protocol: &ssr_node.protocol,
+                protocol_param: ssr_node.protocol_param.as_deref(),
+            }),
+
+            Node::Hysteria(hysteria_node) => Some(StashProxy::Hysteria {
+                name: hysteria_node.get_display_name(),
+                server: &hysteria_node.server,
+                // Stash doesn't support port hopping.
+                port: hysteria_node.get_port(),
+                auth_str: hysteria_node.auth.as_deref(),
+                obfs: hysteria_node.obfs.as_deref(),
+                protocol: hysteria_node.protocol.map(|protocol| protocol.to_string()),
+                up_speed: hysteria_node.up.to_mbps()?,
+                down_speed: hysteria_node.down.to_mbps()?,
+                sni: hysteria_node.tls.sni.as_deref(),
+                alpn: hysteria_node.tls.alpn.as_deref(),
+                skip_cert_verify: hysteria_node.tls.insecure,
+            }),
+
+            Node::Hysteria2(hysteria2_node) => Some(StashProxy::Hysteria2 {
+                name: hysteria2_node.get_display_name(),
+                server: &hysteria2_node.server,
+                // Stash doesn't support port hopping.
+                port: hysteria2_node.get_port(),
+                auth: hysteria2_node.auth.as_deref(),
+                obfs: hysteria2_node.obfs.as_ref().map(|obfs| match obfs {
+                    Hysteria2Obfuscation::Salamander { .. } => "salamander",
+                }),
+                obfs_password: hysteria2_node.obfs.as_ref().map(|obfs| match obfs {
+                    Hysteria2Obfuscation::Salamander { password } => password.as_ref(),
+                }),
+                up_speed: hysteria2_node.up.as_ref().and_then(|up| up.to_mbps()),
+                down_speed: hysteria2_node.down.as_ref().and_then(|down| down.to_mbps()),
+                sni: hysteria2_node.tls.sni.as_deref(),
+                alpn: hysteria2_node.tls.alpn.as_deref(),
+                skip_cert_verify: hysteria2_node.tls.insecure,
+            }),
+
+            Node::Wireguard(wireguard_node) => Some(StashProxy::Wireguard {
+                name: wireguard_node.get_display_name(),
+                server: &wireguard_node.server,
+                port: wireguard_node.port,
+                ip: wireguard_node.ip.map(|ip| ip.to_string()),
+                ipv6: wireguard_node.ipv6.map(|ipv6| ipv6.to_string()),
+                private_key: &wireguard_node.private_key,
+                public_key: &wireguard_node.public_key,
+                preshared_key: wireguard_node.pre_shared_key.as_deref(),
+                reserved: wireguard_node.reserved,
+            }),
+
+            Node::Vmess(vmess_node) => Some(StashProxy::Vmess {
+                name: vmess_node.get_display_name(),
+                server: &vmess_node.server,
+                port: vmess_node.port,
+                uuid: vmess_node.uuid.to_string(),
+                alter_id: vmess_node.alter_id.unwrap_or(0),
+                cipher: vmess_node
+                    .security
+                    .map_or("auto", |security| security.get_alias()),
+                tls: vmess_node.tls.as_ref().map(|_| true),
+                skip_cert_verify: vmess_node.tls.as_ref().and_then(|tls| tls.insecure),
+                servername: vmess_node.tls.as_ref().and_then(|tls| tls.sni.as_deref()),
+                alpn: vmess_node.tls.as_ref().and_then(|tls| tls.alpn.as_deref()),
+                transport: vmess_node.transport.as_ref().map(Into::into),
+            }),
+
+            Node::Vless(vless_node) => Some(StashProxy::Vless {
+                name: vless_node.get_display_name(),
+                server: &vless_node.server,
+                port: vless_node.port,
+                uuid: vless_node.uuid.to_string(),
+                flow: vless_node.flow.as_deref(),
+                tls: vless_node.is_tls_enabled().then_some(true),
+                servername: vless_node.tls.as_ref().and_then(|tls| tls.sni.as_deref()),
+                skip_cert_verify: vless_node.tls.as_ref().and_then(|tls| tls.insecure),
+                alpn: vless_node.tls.as_ref().and_then(|tls| tls.alpn.as_deref()),
+                client_fingerprint: vless_node.fingerprint.as_deref(),
+                reality_opts: vless_node
+                    .reality
+                    .as_ref()
+                    .map(|reality| ClashMetaRealityOpts {
+                        public_key: &reality.public_key,
+                        short_id: reality.short_id.as_deref(),
+                    }),
+                transport: vless_node.transport.as_ref().map(Into::into),
+            }),
+
+            Node::Trojan(trojan_node) => {
+                // Stash only supports WebSocket and gRPC as the transport of Trojan.
+                if matches!(trojan_node.transport, Some(Transport::H2 { .. })) {
+                    return None;
+                }
+
+                Some(StashProxy::Trojan {
+                    name: trojan_node.get_display_name(),
+                    server: &trojan_node.server,
+                    port: trojan_node.port,
+                    password: &trojan_node.password,
+                    sni: trojan_node.tls.sni.as_deref(),
+                    alpn: trojan_node.tls.alpn.as_deref(),
+                    skip_cert_verify: trojan_node.tls.insecure,
+                    transport: trojan_node.transport.as_ref().map(Into::into),
+                })
+            }
+
+            Node::Tuic(tuic_node) => Some(StashProxy::Tuic {
+                name: tuic_node.get_display_name(),
+                server: &tuic_node.server,
+                port: tuic_node.port,
+                // Nodes with both UUID and password are TUIC v5.
+                version: 5,
+                uuid: tuic_node.uuid.to_string(),
+                password: &tuic_node.password,
+                congestion_controller: tuic_node
+                    .congestion_control
+                    .map(|congestion_control| congestion_control.get_alias()),
+                udp_relay_mode: tuic_node
+                    .udp_relay_mode
+                    .map(|udp_relay_mode| udp_relay_mode.get_alias()),
+                sni: tuic_node.tls.sni.as_deref(),
+                alpn: tuic_node.tls.alpn.as_deref(),
+                skip_cert_verify: tuic_node.tls.insecure,
+            }),
+
+            Node::Socks5(socks5_node) => Some(StashProxy::Socks5 {
+                name: socks5_node.get_display_name(),
+                server: &socks5_node.server,
+                port: socks5_node.port,
+                username: socks5_node.username.as_deref(),
+                password: socks5_node.password.as_deref(),
+                tls: socks5_node.tls.as_ref().map(|_| true),
+                skip_cert_verify: socks5_node.tls.as_ref().and_then(|tls| tls.insecure),
+            }),
+
+            Node::Http(http_node) => Some(StashProxy::Http {
+                name: http_node.get_display_name(),
+                server: &http_node.server,
+                port: http_node.port,
+                username: http_node.username.as_deref(),
+                password: http_node.password.as_deref(),
+                tls: http_node.tls.as_ref().map(|_| true),
+                skip_cert_verify: http_node.tls.as_ref().and_then(|tls| tls.insecure),
+                sni: http_node.tls.as_ref().and_then(|tls| tls.sni.as_deref()),
+            }),
+
+            Node::Snell(snell_node) => {
+                // Stash only supports Snell v1 to v3.
+                if matches!(snell_node.version, Some(version) if version > 3) {
+                    return None;
+                }
+
+                Some(StashProxy::Snell {
+                    name: snell_node.get_display_name(),
+                    server: &snell_node.server,
+                    port: snell_node.port,
+                    psk: &snell_node.psk,
+                    version: snell_node.version,
+                    obfs_opts: snell_node
+                        .obfs
+                        .as_ref()
+                        .map(|obfs| ClashMetaSsPluginObfsOpts {
+                            mode: Some(obfs.mode.into()),
+                            host: obfs.host.clone(),
+                        }),
+                })
+            }
+        }
+    }
+
+    fn serialize_nodes<'a, T: Iterator<Item = Self::Node<'a>>>(
+        &self,
+        nodes: T,
+        _options: super::NodesSerializationOptions,
+    ) -> String {
+        let nodes: Vec<_> = nodes.collect();
+        to_string(&nodes).unwrap()
+    }
+}
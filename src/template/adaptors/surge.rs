@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use crate::node::common::{TlsOptions, Transport};
+use crate::node::snell::SnellObfsOpts;
 use crate::node::ss::{ObfsOpts, Plugin as SsPlugin};
 use crate::node::{GetNodeName, Node};
 use crate::template::functions::gen_wireguard_node_id;
@@ -33,6 +35,59 @@ pub enum ProxyType<'a> {
     Wireguard {
         section_name: String,
     },
+
+    Vmess {
+        host: &'a str,
+        port: u16,
+        username: String,
+        ws_path: Option<&'a str>,
+        ws_host: Option<&'a str>,
+        ws: bool,
+        tls: Option<&'a TlsOptions>,
+        vmess_aead: bool,
+    },
+
+    Trojan {
+        host: &'a str,
+        port: u16,
+        password: &'a str,
+        ws: bool,
+        ws_path: Option<&'a str>,
+        ws_host: Option<&'a str>,
+        tls: &'a TlsOptions,
+    },
+
+    TuicV5 {
+        host: &'a str,
+        port: u16,
+        uuid: String,
+        password: &'a str,
+        tls: &'a TlsOptions,
+    },
+
+    Socks5 {
+        host: &'a str,
+        port: u16,
+        username: Option<&'a str>,
+        password: Option<&'a str>,
+        tls: Option<&'a TlsOptions>,
+    },
+
+    Http {
+        host: &'a str,
+        port: u16,
+        username: Option<&'a str>,
+        password: Option<&'a str>,
+        tls: Option<&'a TlsOptions>,
+    },
+
+    Snell {
+        host: &'a str,
+        port: u16,
+        psk: &'a str,
+        version: Option<u8>,
+        obfs: Option<&'a SnellObfsOpts>,
+    },
 }
 impl Display for ProxyType<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -81,6 +136,165 @@ impl Display for ProxyType<'_> {
             Self::Wireguard { section_name } => {
                 write!(f, "wireguard, section-name={section_name}")?;
             }
+
+            Self::Vmess {
+                host,
+                port,
+                username,
+                ws,
+                ws_path,
+                ws_host,
+                tls,
+                vmess_aead,
+            } => {
+                write!(f, "vmess, {host}, {port}, username={username}")?;
+
+                if *ws {
+                    write!(f, ", ws=true")?;
+
+                    if let Some(ws_path) = ws_path {
+                        write!(f, ", ws-path={ws_path}")?;
+                    }
+
+                    if let Some(ws_host) = ws_host {
+                        write!(f, ", ws-headers=Host:{ws_host}")?;
+                    }
+                }
+
+                if let Some(tls) = tls {
+                    write!(f, ", tls=true")?;
+
+                    if let Some(sni) = &tls.sni {
+                        write!(f, ", sni={sni}")?;
+                    }
+
+                    if let Some(insecure) = tls.insecure {
+                        write!(f, ", skip-cert-verify={insecure}")?;
+                    }
+                }
+
+                write!(f, ", vmess-aead={vmess_aead}")?;
+            }
+
+            Self::Trojan {
+                host,
+                port,
+                password,
+                ws,
+                ws_path,
+                ws_host,
+                tls,
+            } => {
+                write!(f, "trojan, {host}, {port}, password={password}")?;
+
+                if *ws {
+                    write!(f, ", ws=true")?;
+
+                    if let Some(ws_path) = ws_path {
+                        write!(f, ", ws-path={ws_path}")?;
+                    }
+
+                    if let Some(ws_host) = ws_host {
+                        write!(f, ", ws-headers=Host:{ws_host}")?;
+                    }
+                }
+
+                if let Some(sni) = &tls.sni {
+                    write!(f, ", sni={sni}")?;
+                }
+
+                if let Some(insecure) = tls.insecure {
+                    write!(f, ", skip-cert-verify={insecure}")?;
+                }
+            }
+
+            Self::TuicV5 {
+                host,
+                port,
+                uuid,
+                password,
+                tls,
+            } => {
+                write!(
+                    f,
+                    "tuic-v5, {host}, {port}, password={password}, uuid={uuid}"
+                )?;
+
+                // Surge only takes a single ALPN value,
+                // and a comma would break the policy line.
+                if let Some(alpn) = tls.alpn.as_ref().and_then(|alpn| alpn.first()) {
+                    write!(f, ", alpn={alpn}")?;
+                }
+
+                if let Some(sni) = &tls.sni {
+                    write!(f, ", sni={sni}")?;
+                }
+
+                if let Some(insecure) = tls.insecure {
+                    write!(f, ", skip-cert-verify={insecure}")?;
+                }
+            }
+
+            Self::Socks5 {
+                host,
+                port,
+                username,
+                password,
+                tls,
+            }
+            | Self::Http {
+                host,
+                port,
+                username,
+                password,
+                tls,
+            } => {
+                let proxy_type = match (self, tls) {
+                    (Self::Socks5 { .. }, None) => "socks5",
+                    (Self::Socks5 { .. }, Some(_)) => "socks5-tls",
+                    (_, None) => "http",
+                    (_, Some(_)) => "https",
+                };
+                write!(f, "{proxy_type}, {host}, {port}")?;
+
+                // Surge reads the credentials by position,
+                // so an empty password is written if only the username is set.
+                if let Some(username) = username {
+                    write!(f, ", {username}, {}", password.unwrap_or("\"\""))?;
+                }
+
+                if let Some(tls) = tls {
+                    if let Some(sni) = &tls.sni {
+                        write!(f, ", sni={sni}")?;
+                    }
+
+                    if let Some(insecure) = tls.insecure {
+                        write!(f, ", skip-cert-verify={insecure}")?;
+                    }
+                }
+            }
+
+            Self::Snell {
+                host,
+                port,
+                psk,
+                version,
+                obfs,
+            } => {
+                write!(f, "snell, {host}, {port}, psk={psk}")?;
+
+                if let Some(version) = version {
+                    write!(f, ", version={version}")?;
+                }
+
+                if let Some(obfs) = obfs {
+                    write!(f, ", obfs={}", obfs.mode)?;
+
+                    if let Some(host) = &obfs.host {
+                        write!(f, ", obfs-host={host}")?;
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -152,6 +366,97 @@ impl Adaptor for Surge {
                     section_name: gen_wireguard_node_id(wireguard_node),
                 },
             }),
+
+            Node::Vmess(vmess_node) => {
+                // Surge only supports WebSocket as the transport of VMess.
+                let (ws, ws_path, ws_host) = match &vmess_node.transport {
+                    Some(Transport::Ws { path, host }) => (true, path.as_deref(), host.as_deref()),
+                    Some(_) => return None,
+                    None => (false, None, None),
+                };
+
+                Some(SurgeProxy {
+                    name: vmess_node.get_display_name(),
+                    proxy: ProxyType::Vmess {
+                        host: &vmess_node.server,
+                        port: vmess_node.port,
+                        username: vmess_node.uuid.to_string(),
+                        ws,
+                        ws_path,
+                        ws_host,
+                        tls: vmess_node.tls.as_ref(),
+                        vmess_aead: matches!(vmess_node.alter_id, None | Some(0)),
+                    },
+                })
+            }
+
+            Node::Vless(_) => None,
+
+            Node::Trojan(trojan_node) => {
+                // Surge only supports WebSocket as the transport of Trojan.
+                let (ws, ws_path, ws_host) = match &trojan_node.transport {
+                    Some(Transport::Ws { path, host }) => (true, path.as_deref(), host.as_deref()),
+                    Some(_) => return None,
+                    None => (false, None, None),
+                };
+
+                Some(SurgeProxy {
+                    name: trojan_node.get_display_name(),
+                    proxy: ProxyType::Trojan {
+                        host: &trojan_node.server,
+                        port: trojan_node.port,
+                        password: &trojan_node.password,
+                        ws,
+                        ws_path,
+                        ws_host,
+                        tls: &trojan_node.tls,
+                    },
+                })
+            }
+
+            Node::Tuic(tuic_node) => Some(SurgeProxy {
+                name: tuic_node.get_display_name(),
+                proxy: ProxyType::TuicV5 {
+                    host: &tuic_node.server,
+                    port: tuic_node.port,
+                    uuid: tuic_node.uuid.to_string(),
+                    password: &tuic_node.password,
+                    tls: &tuic_node.tls,
+                },
+            }),
+
+            Node::Socks5(socks5_node) => Some(SurgeProxy {
+                name: socks5_node.get_display_name(),
+                proxy: ProxyType::Socks5 {
+                    host: &socks5_node.server,
+                    port: socks5_node.port,
+                    username: socks5_node.username.as_deref(),
+                    password: socks5_node.password.as_deref(),
+                    tls: socks5_node.tls.as_ref(),
+                },
+            }),
+
+            Node::Http(http_node) => Some(SurgeProxy {
+                name: http_node.get_display_name(),
+                proxy: ProxyType::Http {
+                    host: &http_node.server,
+                    port: http_node.port,
+                    username: http_node.username.as_deref(),
+                    password: http_node.password.as_deref(),
+                    tls: http_node.tls.as_ref(),
+                },
+            }),
+
+            Node::Snell(snell_node) => Some(SurgeProxy {
+                name: snell_node.get_display_name(),
+                proxy: ProxyType::Snell {
+                    host: &snell_node.server,
+                    port: snell_node.port,
+                    psk: &snell_node.psk,
+                    version: snell_node.version,
+                    obfs: snell_node.obfs.as_ref(),
+                },
+            }),
         }
     }
 
@@ -167,3 +472,42 @@ impl Adaptor for Surge {
             .join("\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::{HttpNode, Socks5Node};
+    use crate::template::adaptors::ConvertNodesToString;
+
+    #[test]
+    fn convert_proxies_with_credentials() {
+        let nodes = [
+            Node::Socks5(Box::new(Socks5Node {
+                remarks: Some(String::from("socks5")),
+                server: String::from("1.2.3.4"),
+                port: 1080,
+                username: Some(String::from("user")),
+                password: None,
+                tls: None,
+            })),
+            Node::Http(Box::new(HttpNode {
+                remarks: Some(String::from("https")),
+                server: String::from("example.com"),
+                port: 443,
+                username: Some(String::from("user")),
+                password: Some(String::from("password")),
+                tls: Some(TlsOptions {
+                    sni: Some(String::from("example.com")),
+                    insecure: None,
+                    alpn: None,
+                }),
+            })),
+        ];
+
+        assert_eq!(
+            Surge.nodes_to_string(nodes.iter(), Default::default()),
+            "socks5 = socks5, 1.2.3.4, 1080, user, \"\"
+https = https, example.com, 443, user, password, sni=example.com"
+        );
+    }
+}
@@ -0,0 +1,583 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::to_string_pretty;
+use serde_with::skip_serializing_none;
+
+use crate::node::common::{TlsOptions, Transport};
+use crate::node::ss::Method as SsMethod;
+use crate::node::{GetNodeName, Node};
+
+use super::Adaptor;
+
+/// Xray outbound
+/// Reference: https://xtls.github.io/config/outbound.html
+#[skip_serializing_none]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XrayOutbound<'a> {
+    tag: String,
+    #[serde(flatten)]
+    settings: XraySettings<'a>,
+    stream_settings: Option<XrayStreamSettings<'a>>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+#[serde(tag = "protocol", content = "settings")]
+pub enum XraySettings<'a> {
+    /// Shadowsocks outbound
+    /// Reference: https://xtls.github.io/config/outbounds/shadowsocks.html
+    #[serde(rename = "shadowsocks")]
+    Shadowsocks {
+        servers: [XrayShadowsocksServer<'a>; 1],
+    },
+
+    /// VMess outbound
+    /// Reference: https://xtls.github.io/config/outbounds/vmess.html
+    #[serde(rename = "vmess")]
+    Vmess { vnext: [XrayVnext<'a>; 1] },
+
+    /// VLESS outbound
+    /// Reference: https://xtls.github.io/config/outbounds/vless.html
+    #[serde(rename = "vless")]
+    Vless { vnext: [XrayVnext<'a>; 1] },
+
+    /// Trojan outbound
+    /// Reference: https://xtls.github.io/config/outbounds/trojan.html
+    #[serde(rename = "trojan")]
+    Trojan { servers: [XrayTrojanServer<'a>; 1] },
+
+    /// SOCKS outbound
+    /// Reference: https://xtls.github.io/config/outbounds/socks.html
+    #[serde(rename = "socks")]
+    Socks { servers: [XrayProxyServer<'a>; 1] },
+
+    /// HTTP outbound
+    /// Reference: https://xtls.github.io/config/outbounds/http.html
+    #[serde(rename = "http")]
+    Http { servers: [XrayProxyServer<'a>; 1] },
+
+    /// WireGuard outbound
+    /// Reference: https://xtls.github.io/config/outbounds/wireguard.html
+    #[serde(rename = "wireguard", rename_all = "camelCase")]
+    Wireguard {
+        secret_key: &'a str,
+        address: Vec<String>,
+        peers: [XrayWireguardPeer<'a>; 1],
+        reserved: Option<[u8; 3]>,
+    },
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+pub struct XrayShadowsocksServer<'a> {
+    address: &'a str,
+    port: u16,
+    method: &'static str,
+    password: &'a str,
+    uot: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct XrayVnext<'a> {
+    address: &'a str,
+    port: u16,
+    users: [XrayUser<'a>; 1],
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+pub struct XrayUser<'a> {
+    id: String,
+    /// Only used by VMess.
+    security: Option<&'static str>,
+    /// Only used by VLESS, which must be `none`.
+    encryption: Option<&'static str>,
+    /// Only used by VLESS.
+    flow: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+pub struct XrayTrojanServer<'a> {
+    address: &'a str,
+    port: u16,
+    password: &'a str,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+pub struct XrayProxyServer<'a> {
+    address: &'a str,
+    port: u16,
+    users: Option<[XrayProxyUser<'a>; 1]>,
+}
+
+#[derive(Serialize)]
+pub struct XrayProxyUser<'a> {
+    user: &'a str,
+    pass: &'a str,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XrayWireguardPeer<'a> {
+    public_key: &'a str,
+    pre_shared_key: Option<&'a str>,
+    endpoint: String,
+}
+
+/// Stream Settings
+/// Reference: https://xtls.github.io/config/transport.html#streamsettingsobject
+#[skip_serializing_none]
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XrayStreamSettings<'a> {
+    network: Option<&'static str>,
+    security: Option<&'static str>,
+    tls_settings: Option<XrayTlsSettings<'a>>,
+    reality_settings: Option<XrayRealitySettings<'a>>,
+    ws_settings: Option<XrayWsSettings<'a>>,
+    http_settings: Option<XrayHttpSettings<'a>>,
+    grpc_settings: Option<XrayGrpcSettings<'a>>,
+}
+impl<'a> XrayStreamSettings<'a> {
+    fn new(transport: Option<&'a Transport>, tls: Option<&'a TlsOptions>) -> Option<Self> {
+        if transport.is_none() && tls.is_none() {
+            return None;
+        }
+
+        let mut stream_settings = Self::default();
+
+        match transport {
+            Some(Transport::Ws { path, host }) => {
+                stream_settings.network = Some("ws");
+                stream_settings.ws_settings = Some(XrayWsSettings {
+                    path: path.as_deref(),
+                    headers: host.as_deref().map(|host| BTreeMap::from([("Host", host)])),
+                });
+            }
+            Some(Transport::H2 { path, host }) => {
+                stream_settings.network = Some("http");
+                stream_settings.http_settings = Some(XrayHttpSettings {
+                    host: host.as_deref(),
+                    path: path.as_deref(),
+                });
+            }
+            Some(Transport::Grpc { service_name }) => {
+                stream_settings.network = Some("grpc");
+                stream_settings.grpc_settings = Some(XrayGrpcSettings {
+                    service_name: service_name.as_deref(),
+                });
+            }
+            None => {}
+        }
+
+        if let Some(tls) = tls {
+            stream_settings.security = Some("tls");
+            stream_settings.tls_settings = Some(XrayTlsSettings {
+                server_name: tls.sni.as_deref(),
+                allow_insecure: tls.insecure,
+                alpn: tls.alpn.as_deref(),
+                fingerprint: None,
+            });
+        }
+
+        Some(stream_settings)
+    }
+}
+
+/// TLS Settings
+/// Reference: https://xtls.github.io/config/transport.html#tlsobject
+#[skip_serializing_none]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XrayTlsSettings<'a> {
+    server_name: Option<&'a str>,
+    allow_insecure: Option<bool>,
+    alpn: Option<&'a [String]>,
+    fingerprint: Option<&'a str>,
+}
+
+/// REALITY Settings
+/// Reference: https://xtls.github.io/config/transport.html#realityobject
+#[skip_serializing_none]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XrayRealitySettings<'a> {
+    server_name: Option<&'a str>,
+    fingerprint: &'a str,
+    public_key: &'a str,
+    short_id: Option<&'a str>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+pub struct XrayWsSettings<'a> {
+    path: Option<&'a str>,
+    headers: Option<BTreeMap<&'static str, &'a str>>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+pub struct XrayHttpSettings<'a> {
+    host: Option<&'a [String]>,
+    path: Option<&'a str>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XrayGrpcSettings<'a> {
+    service_name: Option<&'a str>,
+}
+
+#[derive(Default)]
+pub struct Xray;
+impl Adaptor for Xray {
+    const ADAPTOR_NAME: &'static str = "xray";
+
+    type Node<'a> = XrayOutbound<'a>;
+
+    fn convert_node<'a>(&self, node: &'a Node) -> Option<Self::Node<'a>> {
+        match node {
+            Node::Ss(ss_node) => {
+                // Xray doesn't support SIP003 plugins.
+                if ss_node.plugin.is_some() {
+                    return None;
+                }
+
+                let method = match ss_node.method {
+                    SsMethod::Ss2022Blake3Aes128Gcm
+                    | SsMethod::Ss2022Blake3Aes256Gcm
+                    | SsMethod::Ss2022Blake3Chacha20Poly1305
+                    | SsMethod::AeadChacha20Poly1305
+                    | SsMethod::AeadAes256Gcm
+                    | SsMethod::AeadAes128Gcm => ss_node.method.get_alias(),
+
+                    // Other ciphers are not supported in Xray.
+                    _ => return None,
+                };
+
+                Some(XrayOutbound {
+                    tag: ss_node.get_display_name(),
+                    settings: XraySettings::Shadowsocks {
+                        servers: [XrayShadowsocksServer {
+                            address: &ss_node.server,
+                            port: ss_node.server_port,
+                            method,
+                            password: &ss_node.password,
+                            uot: ss_node.udp_over_tcp,
+                        }],
+                    },
+                    stream_settings: None,
+                })
+            }
+
+            Node::Ssr(_) => None,
+
+            Node::Hysteria(_) => None,
+
+            Node::Hysteria2(_) => None,
+
+            Node::Wireguard(wireguard_node) => Some(XrayOutbound {
+                tag: wireguard_node.get_display_name(),
+                settings: XraySettings::Wireguard {
+                    secret_key: &wireguard_node.private_key,
+                    address: [
+                        wireguard_node.ip.map(|ip| format!("{ip}/32")),
+                        wireguard_node.ipv6.map(|ipv6| format!("{ipv6}/128")),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+                    peers: [XrayWireguardPeer {
+                        public_key: &wireguard_node.public_key,
+                        pre_shared_key: wireguard_node.pre_shared_key.as_deref(),
+                        endpoint: if wireguard_node.server.contains(':') {
+                            format!("[{}]:{}", wireguard_node.server, wireguard_node.port)
+                        } else {
+                            format!("{}:{}", wireguard_node.server, wireguard_node.port)
+                        },
+                    }],
+                    reserved: wireguard_node.reserved,
+                },
+                stream_settings: None,
+            }),
+
+            Node::Vmess(vmess_node) => {
+                // Xray only supports VMess AEAD, which requires `alterId` to be 0.
+                if matches!(vmess_node.alter_id, Some(alter_id) if alter_id > 0) {
+                    return None;
+                }
+
+                Some(XrayOutbound {
+                    tag: vmess_node.get_display_name(),
+                    settings: XraySettings::Vmess {
+                        vnext: [XrayVnext {
+                            address: &vmess_node.server,
+                            port: vmess_node.port,
+                            users: [XrayUser {
+                                id: vmess_node.uuid.to_string(),
+                                security: vmess_node.security.map(|security| security.get_alias()),
+                                encryption: None,
+                                flow: None,
+                            }],
+                        }],
+                    },
+                    stream_settings: XrayStreamSettings::new(
+                        vmess_node.transport.as_ref(),
+                        vmess_node.tls.as_ref(),
+                    ),
+                })
+            }
+
+            Node::Vless(vless_node) => {
+                let mut stream_settings =
+                    XrayStreamSettings::new(vless_node.transport.as_ref(), vless_node.tls.as_ref());
+
+                if let Some(tls_settings) = stream_settings
+                    .as_mut()
+                    .and_then(|stream_settings| stream_settings.tls_settings.as_mut())
+                {
+                    tls_settings.fingerprint = vless_node.fingerprint.as_deref();
+                }
+
+                // REALITY implies TLS, so the stream settings are needed even without `tls`.
+                if let Some(reality) = &vless_node.reality {
+                    let stream_settings = stream_settings.get_or_insert_with(Default::default);
+                    let tls_settings = stream_settings.tls_settings.take();
+                    stream_settings.security = Some("reality");
+                    stream_settings.reality_settings = Some(XrayRealitySettings {
+                        server_name: tls_settings.and_then(|tls| tls.server_name),
+                        // Fingerprint is required by REALITY in Xray,
+                        // so fallback to `chrome` when the fingerprint is not specified.
+                        fingerprint: vless_node.fingerprint.as_deref().unwrap_or("chrome"),
+                        public_key: &reality.public_key,
+                        short_id: reality.short_id.as_deref(),
+                    });
+                }
+
+                Some(XrayOutbound {
+                    tag: vless_node.get_display_name(),
+                    settings: XraySettings::Vless {
+                        vnext: [XrayVnext {
+                            address: &vless_node.server,
+                            port: vless_node.port,
+                            users: [XrayUser {
+                                id: vless_node.uuid.to_string(),
+                                security: None,
+                                encryption: Some("none"),
+                                flow: vless_node.flow.as_deref(),
+                            }],
+                        }],
+                    },
+                    stream_settings,
+                })
+            }
+
+            Node::Trojan(trojan_node) => Some(XrayOutbound {
+                tag: trojan_node.get_display_name(),
+                settings: XraySettings::Trojan {
+                    servers: [XrayTrojanServer {
+                        address: &trojan_node.server,
+                        port: trojan_node.port,
+                        password: &trojan_node.password,
+                    }],
+                },
+                stream_settings: XrayStreamSettings::new(
+                    trojan_node.transport.as_ref(),
+                    Some(&trojan_node.tls),
+                ),
+            }),
+
+            Node::Tuic(_) => None,
+
+            Node::Socks5(socks5_node) => Some(XrayOutbound {
+                tag: socks5_node.get_display_name(),
+                settings: XraySettings::Socks {
+                    servers: [XrayProxyServer {
+                        address: &socks5_node.server,
+                        port: socks5_node.port,
+                        users: get_proxy_users(
+                            socks5_node.username.as_deref(),
+                            socks5_node.password.as_deref(),
+                        ),
+                    }],
+                },
+                stream_settings: XrayStreamSettings::new(None, socks5_node.tls.as_ref()),
+            }),
+
+            Node::Http(http_node) => Some(XrayOutbound {
+                tag: http_node.get_display_name(),
+                settings: XraySettings::Http {
+                    servers: [XrayProxyServer {
+                        address: &http_node.server,
+                        port: http_node.port,
+                        users: get_proxy_users(
+                            http_node.username.as_deref(),
+                            http_node.password.as_deref(),
+                        ),
+                    }],
+                },
+                stream_settings: XrayStreamSettings::new(None, http_node.tls.as_ref()),
+            }),
+
+            Node::Snell(_) => None,
+        }
+    }
+
+    fn serialize_nodes<'a, T: Iterator<Item = Self::Node<'a>>>(
+        &self,
+        nodes: T,
+        options: super::NodesSerializationOptions,
+    ) -> String {
+        let nodes: Vec<_> = nodes.collect();
+
+        if nodes.is_empty() {
+            return String::from("");
+        }
+
+        let mut output = to_string_pretty(&nodes).unwrap();
+
+        if options.include_array_brackets {
+            output
+        } else {
+            output.pop();
+            output.pop();
+            output.split_off(2)
+        }
+    }
+}
+
+fn get_proxy_users<'a>(
+    username: Option<&'a str>,
+    password: Option<&'a str>,
+) -> Option<[XrayProxyUser<'a>; 1]> {
+    username.map(|user| {
+        [XrayProxyUser {
+            user,
+            pass: password.unwrap_or(""),
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::node::vless::RealityOptions;
+    use crate::node::{SsNode, VlessNode};
+    use crate::template::adaptors::{ConvertNodesToString, NodesSerializationOptions};
+
+    fn ss_2022_node() -> Node {
+        Node::Ss(Box::new(SsNode {
+            id: None,
+            remarks: Some(String::from("ss-2022")),
+            server: String::from("example.com"),
+            server_port: 8388,
+            password: String::from("5mOQSa20Kt6ay2LXruBoHQ=="),
+            method: SsMethod::Ss2022Blake3Aes128Gcm,
+            udp: None,
+            udp_over_tcp: Some(true),
+            plugin: None,
+        }))
+    }
+
+    #[test]
+    fn convert_ss_2022_node() {
+        assert_eq!(
+            Xray.nodes_to_string([ss_2022_node()].iter(), Default::default()),
+            r#"[
+  {
+    "tag": "ss-2022",
+    "protocol": "shadowsocks",
+    "settings": {
+      "servers": [
+        {
+          "address": "example.com",
+          "port": 8388,
+          "method": "2022-blake3-aes-128-gcm",
+          "password": "5mOQSa20Kt6ay2LXruBoHQ==",
+          "uot": true
+        }
+      ]
+    }
+  }
+]"#
+        );
+    }
+
+    #[test]
+    fn convert_vless_reality_node_without_tls() {
+        let node = Node::Vless(Box::new(VlessNode {
+            remarks: Some(String::from("vless-reality")),
+            server: String::from("example.com"),
+            port: 443,
+            uuid: Uuid::parse_str("b831381d-6324-4d53-ad4f-8cda48b30811").unwrap(),
+            flow: Some(String::from("xtls-rprx-vision")),
+            transport: None,
+            tls: None,
+            fingerprint: None,
+            reality: Some(RealityOptions {
+                public_key: String::from("public_key"),
+                short_id: Some(String::from("0123")),
+            }),
+        }));
+
+        assert_eq!(
+            Xray.nodes_to_string([node].iter(), Default::default()),
+            r#"[
+  {
+    "tag": "vless-reality",
+    "protocol": "vless",
+    "settings": {
+      "vnext": [
+        {
+          "address": "example.com",
+          "port": 443,
+          "users": [
+            {
+              "id": "b831381d-6324-4d53-ad4f-8cda48b30811",
+              "encryption": "none",
+              "flow": "xtls-rprx-vision"
+            }
+          ]
+        }
+      ]
+    },
+    "streamSettings": {
+      "security": "reality",
+      "realitySettings": {
+        "fingerprint": "chrome",
+        "publicKey": "public_key",
+        "shortId": "0123"
+      }
+    }
+  }
+]"#
+        );
+    }
+
+    #[test]
+    fn convert_nodes_without_array_brackets() {
+        let output = Xray.nodes_to_string(
+            [ss_2022_node(), ss_2022_node()].iter(),
+            NodesSerializationOptions {
+                include_array_brackets: false,
+            },
+        );
+
+        assert!(output.starts_with("  {\n    \"tag\": \"ss-2022\""));
+        assert!(output.ends_with("\n  }"));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&format!("[{output}]"))
+                .unwrap()
+                .as_array()
+                .map(Vec::len),
+            Some(2)
+        );
+    }
+}
@@ -57,8 +57,9 @@ impl Function for GetSurgeWgNodes<'_> {
                 if let Some(reserved) = wg_node.reserved {
                     write!(
                         &mut wg_node_string,
-                        "\npeer = (public-key = {}, allowed-ips = \"0.0.0.0/0, ::/0\", endpoint = {}:{}, client-id = {}/{}/{})",
+                        "\npeer = (public-key = {}, allowed-ips = \"{}\", endpoint = {}:{}, client-id = {}/{}/{})",
                         wg_node.public_key,
+                        wg_node.get_allowed_ips().join(", "),
                         wg_node.server,
                         wg_node.port,
                         reserved[0],
@@ -68,8 +69,9 @@ impl Function for GetSurgeWgNodes<'_> {
                 } else {
                     write!(
                         &mut wg_node_string,
-                        "\npeer = (public-key = {}, allowed-ips = \"0.0.0.0/0, ::/0\", endpoint = {}:{}",
+                        "\npeer = (public-key = {}, allowed-ips = \"{}\", endpoint = {}:{}",
                         wg_node.public_key,
+                        wg_node.get_allowed_ips().join(", "),
                         wg_node.server,
                         wg_node.port,
                     ).unwrap();
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::Ipv6Addr;
+
+use serde_json::Value;
+use tera::{Error, Function};
+
+use crate::node::wireguard::WireguardNode;
+use crate::node::{GetNodeName, Node};
+use crate::template::TemplateArgs;
+
+use super::{get_filtered_nodes_by_function_args, RingerFunctions};
+
+/// Format a WireGuard node as a wg-quick configuration file.
+/// Reference: https://man7.org/linux/man-pages/man8/wg-quick.8.html
+fn gen_wg_quick_conf(node: &WireguardNode) -> String {
+    let mut conf = format!("[Interface]\nPrivateKey = {}", node.private_key);
+
+    let addresses: Vec<String> = node
+        .ip
+        .map(|ip| format!("{ip}/32"))
+        .into_iter()
+        .chain(node.ipv6.map(|ipv6| format!("{ipv6}/128")))
+        .collect();
+    if !addresses.is_empty() {
+        write!(&mut conf, "\nAddress = {}", addresses.join(", ")).unwrap();
+    }
+
+    write!(&mut conf, "\n\n[Peer]\nPublicKey = {}", node.public_key).unwrap();
+
+    if let Some(pre_shared_key) = &node.pre_shared_key {
+        write!(&mut conf, "\nPresharedKey = {}", pre_shared_key).unwrap();
+    }
+
+    write!(
+        &mut conf,
+        "\nAllowedIPs = {}",
+        node.get_allowed_ips().join(", ")
+    )
+    .unwrap();
+
+    if node.server.parse::<Ipv6Addr>().is_ok() {
+        write!(&mut conf, "\nEndpoint = [{}]:{}", node.server, node.port).unwrap();
+    } else {
+        write!(&mut conf, "\nEndpoint = {}:{}", node.server, node.port).unwrap();
+    }
+
+    // The standard WireGuard implementation doesn't support the reserved field,
+    // so we only keep it as a comment for reference.
+    if let Some(reserved) = node.reserved {
+        write!(
+            &mut conf,
+            "\n# Reserved = {}, {}, {}",
+            reserved[0], reserved[1], reserved[2]
+        )
+        .unwrap();
+    }
+
+    conf.push('\n');
+    conf
+}
+
+pub struct GetWireguardConf<'a>(&'a TemplateArgs<'a>);
+impl<'a> GetWireguardConf<'a> {
+    pub fn new(args: &'a TemplateArgs) -> Self {
+        Self(args)
+    }
+}
+impl RingerFunctions for GetWireguardConf<'_> {
+    const NAME: &'static str = "get_wireguard_conf";
+}
+impl Function for GetWireguardConf<'_> {
+    fn call(&self, args: &HashMap<String, Value>) -> tera::Result<Value> {
+        let name = match args.get("name") {
+            Some(Value::String(name)) => name,
+            Some(name) => {
+                return Err(Error::msg(format!(
+                    "Function `{}` received an incorrect type for arg `name`: \
+                        get `{name}` but expected String",
+                    Self::NAME,
+                )))
+            }
+            None => {
+                return Err(Error::msg(format!(
+                    "Function `{}` requires the arg `name`",
+                    Self::NAME,
+                )))
+            }
+        };
+
+        let mut nodes = get_filtered_nodes_by_function_args(Self::NAME, self.0, args)?;
+
+        match nodes.find(|node| &node.get_display_name() == name) {
+            Some(Node::Wireguard(wg_node)) => Ok(Value::String(gen_wg_quick_conf(wg_node))),
+            Some(_) => Err(Error::msg(format!(
+                "Function `{}` received an incorrect value for arg `name`: \
+                    node `{name}` is not a WireGuard node",
+                Self::NAME,
+            ))),
+            None => Err(Error::msg(format!(
+                "Function `{}` received an incorrect value for arg `name`: \
+                    node `{name}` doesn't exists",
+                Self::NAME,
+            ))),
+        }
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gen_full_wg_quick_conf() {
+        let node = WireguardNode {
+            remarks: Some(String::from("wg")),
+            server: String::from("2606:4700:d0::a29f:c001"),
+            port: 2408,
+            ip: Some("172.16.0.2".parse().unwrap()),
+            ipv6: Some("2606:4700:110:8a36::2".parse().unwrap()),
+            private_key: String::from("private_key"),
+            public_key: String::from("public_key"),
+            pre_shared_key: Some(String::from("pre_shared_key")),
+            reserved: Some([1, 2, 3]),
+            allowed_ips: None,
+        };
+
+        assert_eq!(
+            gen_wg_quick_conf(&node),
+            "[Interface]
+PrivateKey = private_key
+Address = 172.16.0.2/32, 2606:4700:110:8a36::2/128
+
+[Peer]
+PublicKey = public_key
+PresharedKey = pre_shared_key
+AllowedIPs = 0.0.0.0/0, ::/0
+Endpoint = [2606:4700:d0::a29f:c001]:2408
+# Reserved = 1, 2, 3
+"
+        );
+    }
+}
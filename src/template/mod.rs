@@ -207,6 +207,38 @@ pub fn get_built_in_templates() -> Vec<Template> {
             requires: vec![],
             output_sub_directories: vec![String::from("surge")],
         },
+        Template {
+            name: Some(String::from("built_in_quantumult_x")),
+            file_name: String::from("quantumult-x.conf"),
+            template: String::from(include_str!(
+                "./built_in_templates/quantumult-x/quantumult-x.conf"
+            )),
+            requires: vec![],
+            output_sub_directories: vec![String::from("quantumult-x")],
+        },
+        Template {
+            name: Some(String::from("built_in_loon")),
+            file_name: String::from("loon.conf"),
+            template: String::from(include_str!("./built_in_templates/loon/loon.conf")),
+            requires: vec![],
+            output_sub_directories: vec![String::from("loon")],
+        },
+        Template {
+            name: Some(String::from("built_in_stash")),
+            file_name: String::from("config.yaml"),
+            template: String::from(include_str!("./built_in_templates/stash/config.yaml")),
+            requires: vec![],
+            output_sub_directories: vec![String::from("stash")],
+        },
+        Template {
+            name: Some(String::from("built_in_shadowsocks_rust")),
+            file_name: String::from("config.json"),
+            template: String::from(include_str!(
+                "./built_in_templates/shadowsocks-rust/config.json"
+            )),
+            requires: vec![],
+            output_sub_directories: vec![String::from("shadowsocks-rust")],
+        },
     ]
 }
 
@@ -231,6 +263,10 @@ impl<'a> RenderEngine<'a> {
             functions::GetSurgeWgNodes::NAME,
             functions::GetSurgeWgNodes::new(args),
         );
+        tera.register_function(
+            functions::GetWireguardConf::NAME,
+            functions::GetWireguardConf::new(args),
+        );
 
         Self {
             templates,
@@ -1,12 +1,28 @@
 use std::hash::Hasher;
+use std::io::Read;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::{anyhow, Context, Result};
+use brotli::Decompressor as BrotliDecompressor;
 use bytes::Bytes;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, LOCATION, USER_AGENT};
 use http::uri::Uri;
-use hyper::{Body, Client};
+use http::{HeaderMap, HeaderValue};
+use hyper::client::connect::Connect;
+use hyper::{Body, Client, Request, Response};
 use hyper_rustls::HttpsConnectorBuilder;
+use log::debug;
 use tokio::fs::read;
+use url::Url;
+
+mod cache;
+mod proxy;
+
+pub use cache::{get_default_cache_directory, ContentCache};
+pub use proxy::parse_proxy;
+use proxy::{get_proxy_from_env, ProxyConnector};
 
 /// A path of a resource.
 #[derive(Debug, Clone)]
@@ -34,52 +50,197 @@ pub fn parse_string_to_path(s: String) -> Result<Path> {
     }
 }
 
-pub async fn load_content_from_url(path: Path) -> Result<Bytes> {
+/// Options used when fetching remote resources.
+#[derive(Debug, Clone, Default)]
+pub struct FetchOptions {
+    /// Extra headers sent with the request.
+    pub headers: HeaderMap,
+
+    /// The maximum time to wait for the whole response.
+    pub timeout: Option<Duration>,
+
+    /// The proxy used to connect to the remote server.
+    /// If not set, the proxy in environment variables (e.g. `HTTPS_PROXY`) will be used.
+    pub proxy: Option<Url>,
+}
+
+pub async fn load_content_from_url(path: Path, options: &FetchOptions) -> Result<Bytes> {
     match path {
-        Path::Url(url) => match url.scheme_str() {
-            Some("http") => {
-                let client = Client::new();
-                let resource_string = format!("remote resource `{url}`");
-                let resp = client
-                    .get(url)
-                    .await
-                    .with_context(|| format!("failed to fetch {resource_string}"))?;
-                Ok(hyper::body::to_bytes(resp.into_body())
-                    .await
-                    .with_context(|| {
-                        format!("failed to convert response body to bytes in {resource_string}")
-                    })?)
+        Path::Url(url) => {
+            let resource_string = format!("remote resource `{url}`");
+
+            let fetch = fetch_remote_resource(url, options);
+            let content = if let Some(timeout) = options.timeout {
+                tokio::time::timeout(timeout, fetch).await.map_err(|_| {
+                    anyhow!(
+                        "timed out after {}s when fetching {}",
+                        timeout.as_secs_f64(),
+                        resource_string
+                    )
+                })?
+            } else {
+                fetch.await
+            };
+
+            content.with_context(|| format!("failed to fetch {resource_string}"))
+        }
+
+        Path::PathBuf(path_buf) => {
+            let read_err_msg = format!("failed to read local file `{}`", path_buf.display());
+            let contents = read(path_buf).await.context(read_err_msg)?;
+            Ok(Bytes::from(contents))
+        }
+    }
+}
+
+/// The maximum number of redirects to follow when fetching a remote resource.
+const MAX_REDIRECTS: usize = 10;
+
+async fn fetch_remote_resource(url: Uri, options: &FetchOptions) -> Result<Bytes> {
+    let mut url = url;
+    let mut headers = options.headers.clone();
+    if !headers.contains_key(ACCEPT_ENCODING) {
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip, br"));
+    }
+
+    for _ in 0..=MAX_REDIRECTS {
+        let proxy = options.proxy.clone().or_else(|| get_proxy_from_env(&url));
+        if let Some(proxy) = &proxy {
+            debug!(
+                "fetching `{}` via proxy `{}:{}`",
+                url,
+                proxy.host_str().unwrap_or_default(),
+                proxy.port_or_known_default().unwrap_or_default()
+            );
+        }
+
+        let resp = match (url.scheme_str(), proxy) {
+            (Some("http"), None) => send_request(Client::new(), url.clone(), &headers).await?,
+
+            (Some("http"), Some(proxy)) => {
+                let client = Client::builder().build(ProxyConnector::new(proxy));
+                send_request(client, url.clone(), &headers).await?
             }
 
-            Some("https") => {
+            (Some("https"), None) => {
                 let https = HttpsConnectorBuilder::new()
                     .with_native_roots()
                     .https_only()
                     .enable_http1()
                     .enable_http2()
                     .build();
-                let client: Client<_, Body> = Client::builder().build(https);
-                let resource_string = format!("remote resource `{url}`");
-                let resp = client
-                    .get(url)
-                    .await
-                    .with_context(|| format!("failed to fetch {resource_string}"))?;
-                Ok(hyper::body::to_bytes(resp.into_body())
-                    .await
-                    .with_context(|| {
-                        format!("failed to convert response body to bytes in {resource_string}")
-                    })?)
+                send_request(Client::builder().build(https), url.clone(), &headers).await?
             }
 
-            _ => unreachable!(),
-        },
+            (Some("https"), Some(proxy)) => {
+                let https = HttpsConnectorBuilder::new()
+                    .with_native_roots()
+                    .https_only()
+                    .enable_http1()
+                    .enable_http2()
+                    .wrap_connector(ProxyConnector::new(proxy));
+                send_request(Client::builder().build(https), url.clone(), &headers).await?
+            }
 
-        Path::PathBuf(path_buf) => {
-            let read_err_msg = format!("failed to read local file `{}`", path_buf.display());
-            let contents = read(path_buf).await.context(read_err_msg)?;
-            Ok(Bytes::from(contents))
+            _ => return Err(anyhow!("Unknown scheme in `{}`", url)),
+        };
+
+        let status = resp.status();
+
+        if status.is_redirection() {
+            if let Some(location) = resp.headers().get(LOCATION) {
+                let location = location
+                    .to_str()
+                    .with_context(|| format!("invalid redirect location from `{url}`"))?;
+                let next_url: Uri = Url::parse(&url.to_string())?
+                    .join(location)
+                    .with_context(|| format!("invalid redirect location `{location}`"))?
+                    .as_str()
+                    .parse()?;
+                debug!("`{}` redirected to `{}` ({})", url, next_url, status);
+
+                // Do not leak credentials or the configured headers to another origin,
+                // only the headers shaping the response are kept.
+                if !is_same_origin(&url, &next_url) {
+                    headers = [USER_AGENT, ACCEPT_ENCODING]
+                        .into_iter()
+                        .filter_map(|name| {
+                            let value = headers.get(&name)?.clone();
+                            Some((name, value))
+                        })
+                        .collect();
+                }
+
+                url = next_url;
+                continue;
+            }
+        }
+
+        if !status.is_success() {
+            return Err(anyhow!("`{}` responded with status {}", url, status));
         }
+
+        let content_encoding = resp.headers().get(CONTENT_ENCODING).map(|encoding| {
+            encoding
+                .to_str()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase()
+        });
+        let body = hyper::body::to_bytes(resp.into_body())
+            .await
+            .context("failed to convert response body to bytes")?;
+
+        return decompress_body(body, content_encoding.as_deref())
+            .with_context(|| format!("failed to decompress response body of `{url}`"));
     }
+
+    Err(anyhow!("too many redirects (more than {})", MAX_REDIRECTS))
+}
+
+/// Whether two URLs share the same scheme, host and port,
+/// e.g. `https://example.com` and `http://example.com` are different origins.
+fn is_same_origin(a: &Uri, b: &Uri) -> bool {
+    a.scheme() == b.scheme() && a.authority() == b.authority()
+}
+
+async fn send_request<C>(
+    client: Client<C, Body>,
+    url: Uri,
+    headers: &HeaderMap,
+) -> Result<Response<Body>>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    let mut request = Request::get(url).body(Body::empty())?;
+    request.headers_mut().extend(headers.clone());
+
+    Ok(client.request(request).await?)
+}
+
+/// Decompress the response body according to the `Content-Encoding` header.
+fn decompress_body(body: Bytes, content_encoding: Option<&str>) -> Result<Bytes> {
+    let mut decompressed = Vec::new();
+
+    match content_encoding {
+        None | Some("") | Some("identity") => return Ok(body),
+
+        Some("gzip") | Some("x-gzip") => {
+            GzDecoder::new(body.as_ref()).read_to_end(&mut decompressed)?;
+        }
+
+        Some("deflate") => {
+            ZlibDecoder::new(body.as_ref()).read_to_end(&mut decompressed)?;
+        }
+
+        Some("br") => {
+            BrotliDecompressor::new(body.as_ref(), 4096).read_to_end(&mut decompressed)?;
+        }
+
+        Some(encoding) => return Err(anyhow!("unsupported content encoding `{}`", encoding)),
+    }
+
+    Ok(Bytes::from(decompressed))
 }
 
 pub struct Blake3Hasher(blake3::Hasher);
@@ -106,3 +267,217 @@ impl Hasher for Blake3Hasher {
         self.0.count()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::io::Write;
+    use std::net::SocketAddr;
+
+    use brotli::CompressorWriter as BrotliCompressor;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use flate2::Compression;
+    use http::header::{AUTHORIZATION, COOKIE};
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Server, StatusCode};
+
+    use super::*;
+
+    const CONTENT: &[u8] = b"ss://YWVzLTI1Ni1nY206cGFzcw@1.2.3.4:8388#Example";
+
+    #[test]
+    fn decompress_gzip_body() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(CONTENT).unwrap();
+        let body = Bytes::from(encoder.finish().unwrap());
+
+        assert_eq!(
+            decompress_body(body.clone(), Some("gzip")).unwrap(),
+            CONTENT
+        );
+        assert_eq!(decompress_body(body, Some("x-gzip")).unwrap(), CONTENT);
+    }
+
+    #[test]
+    fn decompress_deflate_body() {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(CONTENT).unwrap();
+        let body = Bytes::from(encoder.finish().unwrap());
+
+        assert_eq!(decompress_body(body, Some("deflate")).unwrap(), CONTENT);
+    }
+
+    #[test]
+    fn decompress_br_body() {
+        let mut encoder = BrotliCompressor::new(Vec::new(), 4096, 5, 22);
+        encoder.write_all(CONTENT).unwrap();
+        let body = Bytes::from(encoder.into_inner());
+
+        assert_eq!(decompress_body(body, Some("br")).unwrap(), CONTENT);
+    }
+
+    #[test]
+    fn decompress_identity_body() {
+        let body = Bytes::from_static(CONTENT);
+
+        assert_eq!(decompress_body(body.clone(), None).unwrap(), CONTENT);
+        assert_eq!(decompress_body(body.clone(), Some("")).unwrap(), CONTENT);
+        assert_eq!(decompress_body(body, Some("identity")).unwrap(), CONTENT);
+    }
+
+    #[test]
+    fn decompress_unknown_encoding_body() {
+        let body = Bytes::from_static(CONTENT);
+
+        assert!(decompress_body(body, Some("zstd")).is_err());
+    }
+
+    /// Start a local HTTP server:
+    /// - `/ok` responds with `ok`
+    /// - `/forbidden` responds with status 403
+    /// - `/header?<name>` responds with the header `<name>`, or `none` if missing
+    /// - `/redirect?<location>` redirects to `<location>` with status 302
+    async fn start_server() -> SocketAddr {
+        let make_service = make_service_fn(|_| async {
+            Ok::<_, Infallible>(service_fn(|request: Request<Body>| async move {
+                let response = match request.uri().path() {
+                    "/ok" => Response::new(Body::from("ok")),
+                    "/forbidden" => Response::builder()
+                        .status(StatusCode::FORBIDDEN)
+                        .body(Body::empty())
+                        .unwrap(),
+                    "/header" => Response::new(Body::from(
+                        request
+                            .headers()
+                            .get(request.uri().query().unwrap_or_default())
+                            .map_or("none", |value| value.to_str().unwrap())
+                            .to_string(),
+                    )),
+                    "/redirect" => Response::builder()
+                        .status(StatusCode::FOUND)
+                        .header(LOCATION, request.uri().query().unwrap_or_default())
+                        .body(Body::empty())
+                        .unwrap(),
+                    _ => Response::builder()
+                        .status(StatusCode::NOT_FOUND)
+                        .body(Body::empty())
+                        .unwrap(),
+                };
+                Ok::<_, Infallible>(response)
+            }))
+        });
+
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    async fn fetch(url: String, options: &FetchOptions) -> Result<Bytes> {
+        load_content_from_url(Path::Url(url.parse().unwrap()), options).await
+    }
+
+    fn get_auth_options() -> FetchOptions {
+        let mut options = FetchOptions::default();
+        options
+            .headers
+            .insert(AUTHORIZATION, HeaderValue::from_static("Bearer token"));
+        options
+            .headers
+            .insert(COOKIE, HeaderValue::from_static("session=secret"));
+        options
+            .headers
+            .insert("x-token", HeaderValue::from_static("secret"));
+        options
+            .headers
+            .insert(USER_AGENT, HeaderValue::from_static("ringer"));
+        options
+    }
+
+    #[tokio::test]
+    async fn follow_redirect() {
+        let addr = start_server().await;
+
+        let content = fetch(
+            format!("http://{addr}/redirect?/ok"),
+            &FetchOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(content, "ok");
+    }
+
+    #[tokio::test]
+    async fn reject_non_success_status() {
+        let addr = start_server().await;
+
+        let error = fetch(format!("http://{addr}/forbidden"), &FetchOptions::default())
+            .await
+            .unwrap_err();
+        let error = format!("{error:#}");
+        assert!(error.contains(&format!("http://{addr}/forbidden")));
+        assert!(error.contains("403"));
+    }
+
+    #[tokio::test]
+    async fn keep_headers_on_same_origin_redirect() {
+        let addr = start_server().await;
+
+        for (name, value) in [
+            ("authorization", "Bearer token"),
+            ("cookie", "session=secret"),
+            ("x-token", "secret"),
+        ] {
+            let content = fetch(
+                format!("http://{addr}/redirect?/header?{name}"),
+                &get_auth_options(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(content, value);
+        }
+    }
+
+    #[tokio::test]
+    async fn strip_headers_on_cross_origin_redirect() {
+        let addr = start_server().await;
+        let other_addr = start_server().await;
+
+        for (name, value) in [
+            ("authorization", "none"),
+            ("cookie", "none"),
+            ("x-token", "none"),
+            ("user-agent", "ringer"),
+        ] {
+            let content = fetch(
+                format!("http://{addr}/redirect?http://{other_addr}/header?{name}"),
+                &get_auth_options(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(content, value);
+        }
+    }
+
+    #[test]
+    fn compare_origins() {
+        let url: Uri = "https://example.com/sub".parse().unwrap();
+
+        assert!(is_same_origin(
+            &url,
+            &"https://example.com/other".parse().unwrap()
+        ));
+        assert!(!is_same_origin(
+            &url,
+            &"http://example.com/sub".parse().unwrap()
+        ));
+        assert!(!is_same_origin(
+            &url,
+            &"https://example.com:8443/sub".parse().unwrap()
+        ));
+        assert!(!is_same_origin(
+            &url,
+            &"https://cdn.example.com/sub".parse().unwrap()
+        ));
+    }
+}
@@ -0,0 +1,134 @@
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use tokio::fs::{read, rename, DirBuilder, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+use super::Blake3Hasher;
+
+/// Used to give every temporary file a unique name,
+/// since providers are cached concurrently.
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Get the default directory of the cache, e.g. `~/.cache/ringer` on Linux.
+pub fn get_default_cache_directory() -> Option<PathBuf> {
+    dirs::cache_dir().map(|cache_dir| cache_dir.join("ringer"))
+}
+
+/// An on-disk cache of the last successfully fetched content of providers.
+#[derive(Debug, Clone)]
+pub struct ContentCache {
+    directory: PathBuf,
+}
+impl ContentCache {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    fn get_path(&self, key: &str) -> PathBuf {
+        let mut hasher = Blake3Hasher::new();
+        hasher.write(key.as_bytes());
+        self.directory
+            .join("providers")
+            .join(hasher.get_hash().to_hex().as_str())
+    }
+
+    /// Load the cached content, returns `None` if nothing is cached.
+    pub async fn load(&self, key: &str) -> Option<Bytes> {
+        read(self.get_path(key)).await.ok().map(Bytes::from)
+    }
+
+    pub async fn store(&self, key: &str, content: &Bytes) -> Result<()> {
+        let path = self.get_path(key);
+        if let Some(parent) = path.parent() {
+            let mut dir_builder = DirBuilder::new();
+            dir_builder.recursive(true);
+            // The cached content contains credentials of nodes,
+            // so keep it private to the current user.
+            #[cfg(unix)]
+            dir_builder.mode(0o700);
+            dir_builder.create(parent).await.with_context(|| {
+                format!("failed to create cache directory `{}`", parent.display())
+            })?;
+        }
+
+        // Write to a temporary file first,
+        // so that an interrupted write never leaves a broken cache behind.
+        let temp_path = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        write_private_file(&temp_path, content)
+            .await
+            .with_context(|| format!("failed to write cache file `{}`", temp_path.display()))?;
+        rename(&temp_path, &path)
+            .await
+            .with_context(|| format!("failed to write cache file `{}`", path.display()))
+    }
+}
+
+async fn write_private_file(path: &Path, content: &Bytes) -> Result<()> {
+    let mut open_options = OpenOptions::new();
+    open_options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    open_options.mode(0o600);
+
+    let mut file = open_options.open(path).await?;
+    file.write_all(content).await?;
+    file.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_cache(name: &str) -> ContentCache {
+        let directory =
+            std::env::temp_dir().join(format!("ringer-test-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&directory);
+        ContentCache::new(directory)
+    }
+
+    #[tokio::test]
+    async fn store_and_load_content() {
+        let cache = get_test_cache("store-and-load");
+
+        assert_eq!(cache.load("key").await, None);
+
+        cache.store("key", &Bytes::from("content")).await.unwrap();
+        cache.store("other", &Bytes::from("other")).await.unwrap();
+        assert_eq!(cache.load("key").await, Some(Bytes::from("content")));
+        assert_eq!(cache.load("other").await, Some(Bytes::from("other")));
+
+        cache.store("key", &Bytes::from("updated")).await.unwrap();
+        assert_eq!(cache.load("key").await, Some(Bytes::from("updated")));
+
+        // No temporary files are left behind.
+        let files = std::fs::read_dir(cache.directory.join("providers"))
+            .unwrap()
+            .count();
+        assert_eq!(files, 2);
+
+        std::fs::remove_dir_all(&cache.directory).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn keep_cache_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let cache = get_test_cache("private");
+        cache.store("key", &Bytes::from("content")).await.unwrap();
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&cache.directory.join("providers")), 0o700);
+        assert_eq!(mode(&cache.get_path("key")), 0o600);
+
+        std::fs::remove_dir_all(&cache.directory).unwrap();
+    }
+}